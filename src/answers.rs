@@ -0,0 +1,140 @@
+//! Expected answers of the days, used to check results after changes.
+//!
+//! Answers of each day are stored in a plain text file
+//! with one line per part in the form `<part> <answer>`.
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// Folder containing the expected answers of all days.
+pub struct AnswerStore {
+    dir: PathBuf,
+}
+
+impl AnswerStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Loads the answers of `day`, which are empty if none were saved yet.
+    pub fn load(&self, day: usize) -> Result<Answers> {
+        let path = self.day_path(day);
+        match fs::read_to_string(&path) {
+            Ok(s) => Answers::parse(&s).with_context(|| format!("in answers file {:?}", path)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Answers::default()),
+            Err(err) => Err(err).with_context(|| format!("error reading answers file {:?}", path)),
+        }
+    }
+
+    /// Saves the answers of `day`, replacing any earlier answers.
+    pub fn save(&self, day: usize, answers: &Answers) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("error creating answers dir {:?}", self.dir))?;
+        let path = self.day_path(day);
+        fs::write(&path, answers.to_string())
+            .with_context(|| format!("error writing answers file {:?}", path))
+    }
+
+    fn day_path(&self, day: usize) -> PathBuf {
+        self.dir.join(day.to_string())
+    }
+}
+
+/// Expected answers of both parts of a day.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers([Option<String>; 2]);
+
+/// Result of checking an answer against the expected one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    Fail { expected: String },
+    New, // no answer is known yet
+}
+
+impl Answers {
+    pub fn get(&self, part: u8) -> Option<&str> {
+        self.0.get(Self::index(part)?)?.as_deref()
+    }
+
+    pub fn set(&mut self, part: u8, answer: &str) {
+        if let Some(i) = Self::index(part) {
+            self.0[i] = Some(answer.to_string());
+        }
+    }
+
+    pub fn check(&self, part: u8, answer: &str) -> Check {
+        match self.get(part) {
+            Some(x) if x == answer => Check::Pass,
+            Some(x) => Check::Fail {
+                expected: x.to_string(),
+            },
+            None => Check::New,
+        }
+    }
+
+    fn parse(s: &str) -> Result<Self> {
+        let mut r = Self::default();
+        for line in s.lines().filter(|l| !l.trim().is_empty()) {
+            let (part, answer) = line
+                .split_once(' ')
+                .with_context(|| format!("invalid line {line}"))?;
+            match part.parse() {
+                Ok(part @ 1..=2) => r.set(part, answer.trim()),
+                _ => bail!("invalid part in line {line}"),
+            }
+        }
+        Ok(r)
+    }
+
+    fn index(part: u8) -> Option<usize> {
+        (1..=2).contains(&part).then(|| part as usize - 1)
+    }
+}
+
+impl std::fmt::Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, answer) in self.0.iter().enumerate() {
+            if let Some(answer) = answer {
+                writeln!(f, "{} {}", i + 1, answer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Check::Pass => write!(f, "PASS"),
+            Check::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Check::New => write!(f, "NEW"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let a = Answers::parse("1 54916\n2 54728\n").unwrap();
+        assert_eq!(a.get(1), Some("54916"));
+        assert_eq!(a.get(2), Some("54728"));
+        assert_eq!(Answers::parse(&a.to_string()).ok(), Some(a.clone()));
+
+        assert_eq!(a.check(1, "54916"), Check::Pass);
+        assert_eq!(
+            a.check(2, "52136"),
+            Check::Fail {
+                expected: "54728".to_string()
+            }
+        );
+        assert_eq!(Answers::default().check(1, "1"), Check::New);
+
+        assert!(Answers::parse("3 1").is_err());
+    }
+}
@@ -0,0 +1,354 @@
+//! Benchmarking of the solutions using repeated runs.
+//!
+//! Each day is run once as warmup, then the specified number of times
+//! to collect timing statistics of the parse step and the parts.
+//!
+//! Reports are kept in a [`History`] so that later runs can be compared
+//! with them using [`compare`].
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{Day, DayResult};
+
+/// Benchmark results of several days.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    /// Git revision of the benchmarked code, if known.
+    #[serde(default)]
+    pub rev: Option<String>,
+
+    /// Time of the benchmark in seconds since the unix epoch.
+    #[serde(default)]
+    pub timestamp: u64,
+
+    /// Number of measured runs per day, excluding the warmup.
+    pub runs: u32,
+    pub days: Vec<DayBench>,
+}
+
+impl Report {
+    /// Creates an empty report with the current time.
+    pub fn new(rev: Option<String>, runs: u32) -> Report {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Report {
+            rev,
+            timestamp,
+            runs,
+            days: vec![],
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Report> {
+        let s = fs::read_to_string(path)
+            .with_context(|| format!("error reading bench file {:?}", path))?;
+        serde_json::from_str(&s).with_context(|| format!("in bench file {:?}", path))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("error creating bench dir {:?}", dir))?;
+        }
+        let s = serde_json::to_string_pretty(self)?;
+        fs::write(path, s).with_context(|| format!("error writing bench file {:?}", path))
+    }
+
+    /// Statistics of all days together.
+    pub fn total(&self) -> Stats {
+        Stats::sum(self.days.iter().map(|d| &d.total))
+    }
+}
+
+/// Benchmark reports of earlier runs, stored as one JSON report per line.
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Loads all reports, oldest first.
+    pub fn load(&self) -> Result<Vec<Report>> {
+        let s = match fs::read_to_string(&self.path) {
+            Ok(s) => s,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("error reading bench history {:?}", self.path))
+            }
+        };
+        s.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(ln, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("in bench history {:?} line {}", self.path, ln + 1))
+            })
+            .collect()
+    }
+
+    /// Appends `report` to the history.
+    pub fn append(&self, report: &Report) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("error creating bench dir {:?}", dir))?;
+        }
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("error opening bench history {:?}", self.path))?;
+        writeln!(f, "{}", serde_json::to_string(report)?)
+            .with_context(|| format!("error writing bench history {:?}", self.path))
+    }
+
+    /// Finds the latest report of the revision starting with `rev`.
+    pub fn find(&self, rev: &str) -> Result<Option<Report>> {
+        let reports = self.load()?;
+        Ok(reports
+            .into_iter()
+            .rev()
+            .find(|r| r.rev.as_deref().is_some_and(|x| x.starts_with(rev))))
+    }
+}
+
+/// Change of the median total time of a day between two reports.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DayChange {
+    pub day: usize,
+    pub old: Duration,
+    pub new: Duration,
+}
+
+impl DayChange {
+    /// Relative change in percent, positive when the day got slower.
+    pub fn percent(&self) -> f64 {
+        let old = self.old.as_secs_f64();
+        if old == 0.0 {
+            return 0.0;
+        }
+        (self.new.as_secs_f64() - old) / old * 100.0
+    }
+
+    /// Reports if the day got slower by more than `threshold` percent.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.percent() > threshold
+    }
+}
+
+/// Compares the days present in both reports.
+pub fn compare(old: &Report, new: &Report) -> Vec<DayChange> {
+    new.days
+        .iter()
+        .filter_map(|nd| {
+            let od = old.days.iter().find(|od| od.day == nd.day)?;
+            Some(DayChange {
+                day: nd.day,
+                old: od.total.median,
+                new: nd.total.median,
+            })
+        })
+        .collect()
+}
+
+/// Benchmark results of a day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayBench {
+    pub day: usize,
+    pub parse: Stats,
+    pub parts: Vec<PartBench>,
+
+    /// Statistics of the parse step and the parts together.
+    pub total: Stats,
+}
+
+/// Benchmark results of a part.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartBench {
+    pub part: u8,
+    pub stats: Stats,
+}
+
+/// Timing statistics of repeated runs, in seconds when serialized.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    #[serde(with = "secs")]
+    pub min: Duration,
+    #[serde(with = "secs")]
+    pub median: Duration,
+    #[serde(with = "secs")]
+    pub mean: Duration,
+    #[serde(with = "secs")]
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Calculates statistics of the samples.
+    pub fn new(samples: &[Duration]) -> Stats {
+        if samples.is_empty() {
+            return Stats::default();
+        }
+
+        let mut v = samples.to_vec();
+        v.sort();
+        let n = v.len();
+        let median = if n.is_multiple_of(2) {
+            (v[n / 2 - 1] + v[n / 2]) / 2
+        } else {
+            v[n / 2]
+        };
+
+        let mean = v.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            v.iter()
+                .map(|d| (d.as_secs_f64() - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Stats {
+            min: v[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+
+    /// Statistics of the sum of independent measurements.
+    pub fn sum<'a>(it: impl Iterator<Item = &'a Stats>) -> Stats {
+        let mut r = Stats::default();
+        let mut variance = 0.0;
+        for s in it {
+            r.min += s.min;
+            r.median += s.median;
+            r.mean += s.mean;
+            variance += s.stddev.as_secs_f64().powi(2);
+        }
+        r.stddev = Duration::from_secs_f64(variance.sqrt());
+        r
+    }
+}
+
+/// Runs `day` once as warmup and then `runs` times, and returns the statistics.
+///
+/// Any error of the day stops the benchmark.
+pub fn bench(
+    day: &dyn Day,
+    i: usize,
+    input: &str,
+    part: Option<u8>,
+    runs: u32,
+) -> Result<DayBench> {
+    run_ok(day, input, part)?;
+
+    let mut parse = vec![];
+    let mut parts: Vec<(u8, Vec<Duration>)> = vec![];
+    let mut total = vec![];
+    for _ in 0..runs {
+        let dr = run_ok(day, input, part)?;
+        parse.push(dr.parse_time);
+        for (n, pr) in dr.parts.iter().enumerate() {
+            if parts.len() <= n {
+                parts.push((pr.part, vec![]));
+            }
+            parts[n].1.push(pr.elapsed);
+        }
+        total.push(dr.elapsed());
+    }
+
+    Ok(DayBench {
+        day: i,
+        parse: Stats::new(&parse),
+        parts: parts
+            .into_iter()
+            .map(|(part, v)| PartBench {
+                part,
+                stats: Stats::new(&v),
+            })
+            .collect(),
+        total: Stats::new(&total),
+    })
+}
+
+fn run_ok(day: &dyn Day, input: &str, part: Option<u8>) -> Result<DayResult> {
+    let mut dr = day.run(input, part);
+    if let Some(err) = dr.error {
+        return Err(err);
+    }
+    if let Some(n) = dr.parts.iter().position(|pr| pr.answer.is_err()) {
+        let pr = dr.parts.swap_remove(n);
+        let err = pr.answer.unwrap_err();
+        return Err(err.context(format!("in part {}", pr.part)));
+    }
+    Ok(dr)
+}
+
+// secs serializes durations as fractional seconds.
+mod secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_f64(d.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+        let v = f64::deserialize(d)?;
+        Duration::try_from_secs_f64(v).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let ms = Duration::from_millis;
+        let s = Stats::new(&[ms(4), ms(2), ms(6), ms(8)]);
+        assert_eq!(s.min, ms(2));
+        assert_eq!(s.median, ms(5));
+        assert_eq!(s.mean, ms(5));
+        assert!((s.stddev.as_secs_f64() - 0.0025820).abs() < 1e-6);
+
+        let t = Stats::sum([s, s].iter());
+        assert_eq!(t.median, ms(10));
+
+        let mut r = Report::new(Some("abc123".to_string()), 4);
+        let json = serde_json::to_string(&s).unwrap();
+        assert_eq!(serde_json::from_str::<Stats>(&json).ok(), Some(s));
+        assert_eq!(r.total(), Stats::default());
+
+        let day = |day, median| DayBench {
+            day,
+            parse: Stats::default(),
+            parts: vec![],
+            total: Stats {
+                median,
+                ..Stats::default()
+            },
+        };
+        let old = Report {
+            days: vec![day(1, ms(10)), day(2, ms(10))],
+            ..r.clone()
+        };
+        r.days = vec![day(2, ms(12)), day(3, ms(1))];
+        let v = compare(&old, &r);
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].day, 2);
+        assert!((v[0].percent() - 20.0).abs() < 1e-9);
+        assert!(v[0].is_regression(10.0));
+        assert!(!v[0].is_regression(25.0));
+    }
+}
@@ -0,0 +1,180 @@
+//! Configuration file with the sessions of several accounts.
+//!
+//! The file has `key = value` lines, the ones before any `[name]` section
+//! header belong to the default profile:
+//!
+//! ```text
+//! session = 53616c7465645f5f...
+//! contact = me@example.com
+//!
+//! [work]
+//! session_file = /home/me/.secrets/aoc-work
+//! ```
+//!
+//! Known keys are `session`, `session_file` (a file containing the session),
+//! `contact` and `base_url`. Lines starting with `#` are comments.
+
+use anyhow::{bail, Context, Result};
+use directories::BaseDirs;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Name of the profile of the keys outside of sections.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Settings of an account.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    pub session: Option<String>,
+    pub session_file: Option<PathBuf>,
+    pub contact: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl Profile {
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_PROFILE
+    }
+
+    /// Session of the profile, read from its session file if needed.
+    pub fn session(&self) -> Result<Option<String>> {
+        if let Some(s) = &self.session {
+            return Ok(Some(s.clone()));
+        }
+        let Some(path) = &self.session_file else {
+            return Ok(None);
+        };
+        read_session_file(path).map(Some)
+    }
+}
+
+/// Reads the session from the file at `path`.
+pub fn read_session_file(path: &Path) -> Result<String> {
+    let s =
+        fs::read_to_string(path).with_context(|| format!("error reading session file {:?}", path))?;
+    Ok(s.trim().to_string())
+}
+
+/// Profiles of the configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    profiles: Vec<Profile>,
+}
+
+impl Config {
+    /// Path of the configuration file, set by `AOC_CONFIG`
+    /// or in the user's config folder.
+    pub fn default_path() -> Option<PathBuf> {
+        match env::var_os("AOC_CONFIG") {
+            Some(path) if !path.is_empty() => Some(PathBuf::from(path)),
+            _ => Some(BaseDirs::new()?.config_dir().join("aoc23").join("config")),
+        }
+    }
+
+    /// Loads the configuration file, which is empty if the file does not exist.
+    pub fn load(path: &Path) -> Result<Config> {
+        match fs::read_to_string(path) {
+            Ok(s) => Config::parse(&s).with_context(|| format!("in config file {:?}", path)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Config::parse(""),
+            Err(err) => Err(err).with_context(|| format!("error reading config file {:?}", path)),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Config> {
+        let mut profiles = vec![Profile {
+            name: DEFAULT_PROFILE.to_string(),
+            ..Profile::default()
+        }];
+        for (ln, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let name = name.trim();
+                check_name(name).with_context(|| format!("line {}", ln + 1))?;
+                if profiles.iter().any(|p| p.name == name) {
+                    bail!("line {}: duplicate profile {}", ln + 1, name);
+                }
+                profiles.push(Profile {
+                    name: name.to_string(),
+                    ..Profile::default()
+                });
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                bail!("line {}: expected key = value", ln + 1);
+            };
+            let value = Some(value.trim().to_string());
+            let p = profiles.last_mut().unwrap();
+            match key.trim() {
+                "session" => p.session = value,
+                "session_file" => p.session_file = value.map(PathBuf::from),
+                "contact" => p.contact = value,
+                "base_url" => p.base_url = value,
+                key => bail!("line {}: unknown key {}", ln + 1, key),
+            }
+        }
+        Ok(Config { profiles })
+    }
+
+    /// Returns the profile `name`. The default profile always exists.
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Names of the profiles, the default one first.
+    pub fn profile_names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.iter().map(|p| p.name.as_str())
+    }
+}
+
+// check_name checks that name can be used in a file name.
+fn check_name(name: &str) -> Result<()> {
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    if name.is_empty() || !name.chars().all(valid) {
+        bail!(
+            "invalid profile name {:?}, use letters, digits, - and _",
+            name
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let c = Config::parse(
+            "# accounts
+session = abc
+
+[work]
+session_file = /tmp/secret
+contact = me@example.com
+",
+        )
+        .unwrap();
+        let p = c.profile(DEFAULT_PROFILE).unwrap();
+        assert_eq!(p.session().unwrap().as_deref(), Some("abc"));
+        assert!(p.is_default());
+        let p = c.profile("work").unwrap();
+        assert_eq!(p.session_file, Some(PathBuf::from("/tmp/secret")));
+        assert_eq!(p.contact.as_deref(), Some("me@example.com"));
+        assert!(c.profile("home").is_none());
+        assert_eq!(c.profile_names().collect::<Vec<_>>(), ["default", "work"]);
+
+        assert!(Config::parse("[a]\n[a]\n").is_err());
+        assert!(Config::parse("[a/b]\n").is_err());
+        assert!(Config::parse("token = x\n").is_err());
+        assert!(Config::parse("session\n").is_err());
+        assert_eq!(Config::parse("").unwrap().profile_names().count(), 1);
+    }
+}
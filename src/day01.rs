@@ -1,22 +1,32 @@
 use anyhow::anyhow;
+use std::fmt::Display;
 
-pub fn run(input: &str) -> anyhow::Result<String> {
-    let p1 = run_calibr(input, calibr_1)?;
-    let p2 = run_calibr(input, calibr_2)?;
+use crate::solution::Solution;
+
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Input = &'a str;
+
+    fn parse(&self, input: &'a str) -> anyhow::Result<&'a str> {
+        Ok(input)
+    }
+
+    fn part1(&self, input: &&str) -> anyhow::Result<impl Display> {
+        run_calibr(input, calibr_1)
+    }
 
     // 2: 52136 too low
-    Ok(format!("{p1} {p2}"))
+    fn part2(&self, input: &&str) -> anyhow::Result<impl Display> {
+        run_calibr(input, calibr_2)
+    }
 }
 
 fn run_calibr(input: &str, f: impl FnMut(&str) -> anyhow::Result<u32>) -> anyhow::Result<u32> {
     input
         .lines()
         .map(f)
-        .fold(Ok(0), |rsum, elem| match (rsum, elem) {
-            (Ok(sum), Ok(e)) => Ok(sum + e),
-            (Err(err), _) => Err(err),
-            (_, Err(err)) => Err(err),
-        })
+        .try_fold(0, |sum, elem| elem.map(|e| sum + e))
 }
 
 fn calibr_1(line: &str) -> anyhow::Result<u32> {
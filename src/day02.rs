@@ -1,11 +1,26 @@
+use std::fmt::Display;
 use std::str::FromStr;
 
 use anyhow::{anyhow, bail, Result};
 
-pub fn run(input: &str) -> Result<String> {
-    let v = input.lines().map(parse_picks).collect::<Result<Vec<_>>>()?;
+use crate::solution::Solution;
 
-    Ok(format!("{} {}", p1(&v), p2(&v)))
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Vec<Game>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Game>> {
+        input.lines().map(parse_picks).collect()
+    }
+
+    fn part1(&self, v: &Vec<Game>) -> Result<impl Display> {
+        Ok(p1(v))
+    }
+
+    fn part2(&self, v: &Vec<Game>) -> Result<impl Display> {
+        Ok(p2(v))
+    }
 }
 
 fn p1(games: &[Game]) -> u32 {
@@ -16,7 +31,8 @@ fn p1(games: &[Game]) -> u32 {
     };
     games
         .iter()
-        .filter_map(|g| (g.picks.iter().all(|p| p.all_lt(&want))).then_some(g.no))
+        .filter(|g| g.picks.iter().all(|p| p.all_lt(&want)))
+        .map(|g| g.no)
         .sum()
 }
 
@@ -36,7 +52,7 @@ fn p2(games: &[Game]) -> u32 {
 }
 
 #[derive(Debug, Clone)]
-struct Game {
+pub struct Game {
     no: u32,
     picks: Vec<Pick>,
 }
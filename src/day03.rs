@@ -1,22 +1,41 @@
 use std::collections::HashMap;
+use std::fmt::Display;
 
 use anyhow::Result;
+use itertools::Itertools;
 
-pub fn run(input: &str) -> Result<String> {
-    Ok(format!("{} {}", p1(input), p2(input)))
+use crate::grid::Grid;
+use crate::solution::Solution;
+
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Vec<Num>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Num>> {
+        nums(input)
+    }
+
+    fn part1(&self, nums: &Vec<Num>) -> Result<impl Display> {
+        Ok(p1(nums))
+    }
+
+    fn part2(&self, nums: &Vec<Num>) -> Result<impl Display> {
+        Ok(p2(nums))
+    }
 }
 
-fn p1(input: &str) -> u32 {
-    nums(input)
-        .iter()
-        .filter_map(|num| num.sym.is_some().then_some(num.value))
+fn p1(nums: &[Num]) -> u32 {
+    nums.iter()
+        .filter(|num| num.sym.is_some())
+        .map(|num| num.value)
         .sum()
 }
 
-fn p2(input: &str) -> u32 {
+fn p2(nums: &[Num]) -> u32 {
     let mut m = HashMap::<_, Vec<_>>::new();
 
-    for num in nums(input) {
+    for num in nums {
         if let Some(sym) = num.sym {
             if sym.c == b'*' {
                 m.entry((sym.x, sym.y)).or_default().push(num.value);
@@ -25,12 +44,13 @@ fn p2(input: &str) -> u32 {
     }
 
     m.values()
-        .filter_map(|v| (v.len() == 2).then(|| v[0] * v[1]))
+        .filter(|v| v.len() == 2)
+        .map(|v| v[0] * v[1])
         .sum()
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Num {
+pub struct Num {
     x: i16,
     y: i16,
     w: i16,
@@ -47,12 +67,12 @@ struct Sym {
     c: u8,
 }
 
-fn nums(input: &str) -> Vec<Num> {
-    let v: Vec<Vec<u8>> = input.lines().map(|l| l.bytes().collect()).collect();
+fn nums(input: &str) -> Result<Vec<Num>> {
+    let grid = Grid::parse(input)?;
 
     let mut nums = vec![];
 
-    for (y, line) in v.iter().enumerate() {
+    for (y, line) in grid.rows().enumerate() {
         let mut acc: Option<Num> = None;
         for (x, dgt) in line.iter().map(|&c| (c as char).to_digit(10)).enumerate() {
             if let Some(dgt) = dgt {
@@ -78,45 +98,29 @@ fn nums(input: &str) -> Vec<Num> {
         }
     }
 
-    let get_sym = |x, y| -> Option<Sym> {
-        if x < 0 || y < 0 {
-            return None;
-        }
-        let ys = y as usize;
-        if ys >= v.len() {
-            return None;
-        }
-        let line = &v[ys];
-        let xs = x as usize;
-        if xs >= line.len() {
-            return None;
-        }
-
-        let c = line[xs];
-        (c != b'.').then_some(Sym { x, y, c })
-    };
-
     let get_num_sym = |num: &Num| -> Option<Sym> {
-        let z = get_sym(num.x - 1, num.y).or_else(|| get_sym(num.x + num.w, num.y));
-        if z.is_some() {
-            return z;
-        }
-
-        for x in (num.x - 1)..=(num.x + num.w) {
-            let z = get_sym(x, num.y - 1).or_else(|| get_sym(x, num.y + 1));
-            if z.is_some() {
-                return z;
-            }
-        }
-
-        None
+        let (x0, x1, y) = (num.x as i32, (num.x + num.w) as i32, num.y as i32);
+        // left and right of the number first, then above and below it from left to right
+        (x0..x1)
+            .flat_map(|x| grid.neighbours8((x, y)))
+            .filter(|&(x, ny)| ny != y || x < x0 || x >= x1)
+            .sorted_by_key(|&(x, ny)| (ny != y, x, ny))
+            .dedup()
+            .find_map(|(x, ny)| {
+                let c = *grid.get((x, ny))?;
+                (c != b'.').then_some(Sym {
+                    x: x as i16,
+                    y: ny as i16,
+                    c,
+                })
+            })
     };
 
     for num in nums.iter_mut() {
         num.sym = get_num_sym(num)
     }
 
-    nums
+    Ok(nums)
 }
 
 #[cfg(test)]
@@ -137,7 +141,17 @@ mod test {
 ...$.*....
 .664.598..
 ";
-        assert_eq!(p1(src), 4361);
-        assert_eq!(p2(src), 467835);
+        let v = nums(src).unwrap();
+        assert_eq!(p1(&v), 4361);
+        assert_eq!(p2(&v), 467835);
+    }
+
+    #[test]
+    fn test_sym_order() {
+        let sym = |src| nums(src).unwrap()[0].sym.unwrap().c;
+        assert_eq!(sym("*..\n$12\n..%\n"), b'$');
+        assert_eq!(sym("..*.\n.12#\n%...\n"), b'#');
+        assert_eq!(sym("..*.\n.12.\n%...\n"), b'%');
+        assert_eq!(sym("...*\n.12.\n..%.\n"), b'%');
     }
 }
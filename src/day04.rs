@@ -1,36 +1,49 @@
 use std::collections::{HashSet, VecDeque};
+use std::fmt::Display;
 
 use anyhow::{anyhow, Result};
 
-pub fn run(input: &str) -> Result<String> {
-    Ok(format!("{} {}", p1(input)?, p2(input)?))
+use crate::solution::Solution;
+
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Vec<Card>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Card>> {
+        input.lines().map(Card::parse).collect()
+    }
+
+    fn part1(&self, cards: &Vec<Card>) -> Result<impl Display> {
+        Ok(p1(cards))
+    }
+
+    fn part2(&self, cards: &Vec<Card>) -> Result<impl Display> {
+        Ok(p2(cards))
+    }
 }
 
-fn p1(input: &str) -> Result<usize> {
-    input.lines().try_fold(0, |total, line| {
-        Card::parse(line).map(|card| total + card.score())
-    })
+fn p1(cards: &[Card]) -> usize {
+    cards.iter().map(|card| card.score()).sum()
 }
 
-fn p2(input: &str) -> Result<usize> {
+fn p2(cards: &[Card]) -> usize {
     let mut nexts = VecDeque::new();
 
-    input.lines().try_fold(0, |total, line| {
-        Card::parse(line).map(|card| {
-            let nc = 1 + nexts.pop_front().unwrap_or(0);
+    cards.iter().fold(0, |total, card| {
+        let nc = 1 + nexts.pop_front().unwrap_or(0);
 
-            if card.wins > nexts.len() {
-                nexts.resize(card.wins, 0);
-            }
-            nexts.iter_mut().take(card.wins).for_each(|n| *n += nc);
+        if card.wins > nexts.len() {
+            nexts.resize(card.wins, 0);
+        }
+        nexts.iter_mut().take(card.wins).for_each(|n| *n += nc);
 
-            total + nc
-        })
+        total + nc
     })
 }
 
 #[allow(unused)]
-struct Card {
+pub struct Card {
     num: u32,
     wins: usize,
 }
@@ -79,7 +92,8 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 ";
-        assert_eq!(p1(src).ok(), Some(13));
-        assert_eq!(p2(src).ok(), Some(30));
+        let cards = Solver.parse(src).unwrap();
+        assert_eq!(p1(&cards), 13);
+        assert_eq!(p2(&cards), 30);
     }
 }
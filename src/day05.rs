@@ -1,12 +1,27 @@
 use anyhow::{anyhow, bail, Context, Result};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let alm = parse_almanac(input)?;
+use crate::solution::Solution;
 
-    Ok(format!("{} {}", p1(&alm)?, p2(&alm)?))
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Almanac;
+
+    fn parse(&self, input: &str) -> Result<Almanac> {
+        parse_almanac(input)
+    }
+
+    fn part1(&self, alm: &Almanac) -> Result<impl Display> {
+        p1(alm)
+    }
+
+    fn part2(&self, alm: &Almanac) -> Result<impl Display> {
+        p2(alm)
+    }
 }
 
-type Seed = i64;
+pub type Seed = i64;
 
 fn p1(alm: &Almanac) -> Result<Seed> {
     alm.locations()
@@ -48,27 +63,30 @@ fn p2(alm: &Almanac) -> Result<Seed> {
     result.ok_or_else(|| anyhow!("no seeds"))
 }
 
-struct Almanac {
-    seeds: Vec<Seed>,
-    map: Map,
+/// Seeds with the combined seed-to-location map.
+pub struct Almanac {
+    pub seeds: Vec<Seed>,
+    pub map: Map,
 }
 
 impl Almanac {
-    fn locations(&self) -> impl Iterator<Item = Seed> + '_ {
+    /// Locations of all seeds.
+    pub fn locations(&self) -> impl Iterator<Item = Seed> + '_ {
         self.seeds.iter().map(|&seed| self.map.apply(seed))
     }
 }
 
-#[derive(Clone)]
-struct Map(Vec<MapEntry>);
+/// Mapping of seed ranges, sorted by source.
+#[derive(Clone, Default)]
+pub struct Map(pub Vec<MapEntry>);
 
 impl Map {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self(vec![])
     }
 
-    // Apply this mapping to the input seed.
-    fn apply(&self, seed: Seed) -> Seed {
+    /// Apply this mapping to the input seed.
+    pub fn apply(&self, seed: Seed) -> Seed {
         let v = &self.0;
         let i = v.partition_point(|x| x.src.1 <= seed);
         if i < v.len() {
@@ -78,9 +96,9 @@ impl Map {
         }
     }
 
-    // Combine two maps (eg. seed-to-soil and soil-to-fertilizer)
-    // into one for efficient search and lookup using apply.
-    fn combine(&self, other: &Self) -> Self {
+    /// Combine two maps (eg. seed-to-soil and soil-to-fertilizer)
+    /// into one for efficient search and lookup using apply.
+    pub fn combine(&self, other: &Self) -> Self {
         // Values in self.dst must be matched with those in other.src,
         // therefore sort inputs accordingly.
         let mut v0 = self.0.clone();
@@ -149,14 +167,15 @@ impl Map {
     }
 }
 
+/// Mapping of the source range `src` to `dst`, both with exclusive ends.
 #[derive(Debug, Copy, Clone)]
-struct MapEntry {
-    src: (Seed, Seed),
-    dst: (Seed, Seed),
+pub struct MapEntry {
+    pub src: (Seed, Seed),
+    pub dst: (Seed, Seed),
 }
 
 impl MapEntry {
-    fn new(src: (Seed, Seed), dst: (Seed, Seed)) -> Self {
+    pub fn new(src: (Seed, Seed), dst: (Seed, Seed)) -> Self {
         assert_eq!(src.1 - src.0, dst.1 - dst.0);
         Self { src, dst }
     }
@@ -187,7 +206,8 @@ impl MapEntry {
     }
 }
 
-fn parse_almanac(input: &str) -> Result<Almanac> {
+/// Parses seeds and maps, combining maps into one.
+pub fn parse_almanac(input: &str) -> Result<Almanac> {
     let mut seeds = vec![];
 
     let mut combined_map = Map::new();
@@ -1,9 +1,27 @@
 use anyhow::{anyhow, bail, Result};
+use std::fmt::Display;
 
 use num::Integer;
 
-pub fn run(input: &str) -> Result<String> {
-    Ok(format!("{} {}", p1(input)?, p2(input)?))
+use crate::solution::Solution;
+
+pub struct Solver;
+
+// Numbers are read differently in each part, so parsing is done by the parts.
+impl<'a> Solution<'a> for Solver {
+    type Input = &'a str;
+
+    fn parse(&self, input: &'a str) -> Result<&'a str> {
+        Ok(input)
+    }
+
+    fn part1(&self, input: &&str) -> Result<impl Display> {
+        p1(input)
+    }
+
+    fn part2(&self, input: &&str) -> Result<impl Display> {
+        p2(input)
+    }
 }
 
 fn p1(input: &str) -> Result<i64> {
@@ -1,10 +1,26 @@
 use anyhow::{anyhow, Result};
 use std::cmp::Reverse;
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let p1 = play(GameType::Simple, input)?;
-    let p2 = play(GameType::WithJoker, input)?;
-    Ok(format!("{p1} {p2}"))
+use crate::solution::Solution;
+
+pub struct Solver;
+
+// Hands are valued differently in each part, so parsing is done by the parts.
+impl<'a> Solution<'a> for Solver {
+    type Input = &'a str;
+
+    fn parse(&self, input: &'a str) -> Result<&'a str> {
+        Ok(input)
+    }
+
+    fn part1(&self, input: &&str) -> Result<impl Display> {
+        play(GameType::Simple, input)
+    }
+
+    fn part2(&self, input: &&str) -> Result<impl Display> {
+        play(GameType::WithJoker, input)
+    }
 }
 
 fn play(t: GameType, input: &str) -> Result<i64> {
@@ -1,8 +1,24 @@
 use anyhow::{anyhow, Result};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let (instr, m) = parse_input(input)?;
-    Ok(format!("{} {}", p1(instr, &m)?, p2(instr, &m)))
+use crate::solution::Solution;
+
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Input = (&'a str, NodeMap<'a>);
+
+    fn parse(&self, input: &'a str) -> Result<(&'a str, NodeMap<'a>)> {
+        parse_input(input)
+    }
+
+    fn part1(&self, (instr, m): &(&str, NodeMap)) -> Result<impl Display> {
+        p1(instr, m)
+    }
+
+    fn part2(&self, (instr, m): &(&str, NodeMap)) -> Result<impl Display> {
+        Ok(p2(instr, m))
+    }
 }
 
 fn p1(instr: &str, m: &NodeMap) -> Result<usize> {
@@ -45,11 +61,12 @@ fn p2(instr: &str, m: &NodeMap) -> usize {
     */
 
     (0..m.node_count())
-        .filter_map(|p| m.is_start_2(p).then(|| m.cycle_len(p, instr)))
+        .filter(|&p| m.is_start_2(p))
+        .map(|p| m.cycle_len(p, instr))
         .fold(1, num::integer::lcm)
 }
 
-fn parse_input(input: &str) -> Result<(&str, NodeMap)> {
+fn parse_input(input: &str) -> Result<(&str, NodeMap<'_>)> {
     let mut it = input.lines();
     let instr = it.next().ok_or_else(|| anyhow!("instruction missing"))?;
 
@@ -60,7 +77,7 @@ fn parse_input(input: &str) -> Result<(&str, NodeMap)> {
     Ok((instr, NodeMap::from(&mut v)?))
 }
 
-struct NodeMap<'a>(Vec<NodeMapEntry<'a>>);
+pub struct NodeMap<'a>(Vec<NodeMapEntry<'a>>);
 
 struct NodeMapEntry<'a> {
     name: &'a str,
@@ -167,11 +184,11 @@ struct Node<'a> {
 }
 
 impl Node<'_> {
-    fn from(line: &str) -> Result<Node> {
+    fn from(line: &str) -> Result<Node<'_>> {
         Self::from_impl(line).ok_or_else(|| anyhow!("invalid node: {line}"))
     }
 
-    fn from_impl(line: &str) -> Option<Node> {
+    fn from_impl(line: &str) -> Option<Node<'_>> {
         let mut it = line
             .split([' ', '=', '(', ')', ','])
             .filter(|e| !e.is_empty());
@@ -1,25 +1,35 @@
 use anyhow::{anyhow, Result};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let (nf, nl) = process(input)?;
-    Ok(format!("{nl} {nf}"))
+use crate::solution::Solution;
+
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Vec<Vec<i64>>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Vec<i64>>> {
+        parse(input)
+    }
+
+    fn part1(&self, v: &Vec<Vec<i64>>) -> Result<impl Display> {
+        Ok(v.iter().map(|x| extrapolate(x.iter().copied()).1).sum::<i64>())
+    }
+
+    fn part2(&self, v: &Vec<Vec<i64>>) -> Result<impl Display> {
+        Ok(v.iter().map(|x| extrapolate(x.iter().copied()).0).sum::<i64>())
+    }
 }
 
-fn process(input: &str) -> Result<(i64, i64)> {
+fn parse(input: &str) -> Result<Vec<Vec<i64>>> {
     input
         .lines()
         .map(|line| {
-            let r = line
-                .split_whitespace()
+            line.split_whitespace()
                 .map(|x| x.parse::<i64>().map_err(|_| anyhow!("invalid number: {x}")))
-                .collect::<Result<Vec<_>, _>>();
-            r.map(|v| extrapolate(v.iter().copied()))
-        })
-        .fold(Ok((0, 0)), |sum, r| match (sum, r) {
-            (Ok((xf, xl)), Ok((yf, yl))) => Ok((xf + yf, xl + yl)),
-            (Err(e), _) => Err(e),
-            (_, Err(e)) => Err(e),
+                .collect::<Result<Vec<_>, _>>()
         })
+        .collect()
 }
 
 fn extrapolate(nums: impl Iterator<Item = i64>) -> (i64, i64) {
@@ -1,29 +1,98 @@
 use crate::grid::{CellP, Dir, Grid, DIRS};
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let (p1, p2) = probl(input)?;
+use crate::solution::Solution;
+use crate::log::Level;
 
-    Ok(format!("{p1} {p2}"))
-}
+pub struct Solver;
 
-fn probl(input: &str) -> Result<(usize, usize)> {
-    let m = Map::parse(input)?;
+// Input is the map and the pipe loop found in it.
+impl Solution<'_> for Solver {
+    type Input = (Map, Vec<CellP>);
 
-    let l = m.find_loop().ok_or_else(|| anyhow!("loop not found"))?;
+    fn parse(&self, input: &str) -> Result<(Map, Vec<CellP>)> {
+        let m = Map::parse(input)?;
+        let l = m.find_loop().ok_or_else(|| anyhow!("loop not found"))?;
+        Ok((m, l))
+    }
 
-    Ok((l.len() / 2, m.count_enclosed(&l)))
+    fn part1(&self, (_, l): &(Map, Vec<CellP>)) -> Result<impl Display> {
+        Ok(l.len() / 2)
+    }
+
+    fn part2(&self, (m, l): &(Map, Vec<CellP>)) -> Result<impl Display> {
+        Ok(m.count_enclosed(l))
+    }
 }
 
-struct Map(Grid<u8>);
+pub struct Map(Grid<Pipe>);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Pipe {
+    Ground,
+    Start,
+    Vertical,
+    Horizontal,
+    NorthEast,
+    NorthWest,
+    SouthWest,
+    SouthEast,
+}
+
+impl TryFrom<u8> for Pipe {
+    type Error = anyhow::Error;
+
+    fn try_from(c: u8) -> Result<Self> {
+        Ok(match c {
+            b'.' => Pipe::Ground,
+            b'S' => Pipe::Start,
+            b'|' => Pipe::Vertical,
+            b'-' => Pipe::Horizontal,
+            b'L' => Pipe::NorthEast,
+            b'J' => Pipe::NorthWest,
+            b'7' => Pipe::SouthWest,
+            b'F' => Pipe::SouthEast,
+            _ => bail!("invalid pipe {:?}", c as char),
+        })
+    }
+}
+
+impl Pipe {
+    // connects reports if the pipe has an end towards d.
+    // The start connects in all directions.
+    fn connects(self, d: Dir) -> bool {
+        use Pipe::*;
+        match d {
+            Dir::North => matches!(self, Vertical | NorthEast | NorthWest | Start),
+            Dir::South => matches!(self, Vertical | SouthWest | SouthEast | Start),
+            Dir::West => matches!(self, Horizontal | NorthWest | SouthWest | Start),
+            Dir::East => matches!(self, Horizontal | NorthEast | SouthEast | Start),
+            _ => false,
+        }
+    }
+
+    fn graphic(self) -> char {
+        match self {
+            Pipe::Ground => '·',
+            Pipe::Start => 'S',
+            Pipe::Vertical => '│',
+            Pipe::Horizontal => '─',
+            Pipe::NorthEast => '└',
+            Pipe::NorthWest => '┘',
+            Pipe::SouthWest => '┐',
+            Pipe::SouthEast => '┌',
+        }
+    }
+}
 
 impl Map {
     fn parse(input: &str) -> Result<Self> {
-        Ok(Self(Grid::parse(input)?))
+        Ok(Self(Grid::parse_cells(input)?))
     }
 
     fn start(&self) -> Option<CellP> {
-        self.0.find(&b'S')
+        self.0.find(&Pipe::Start)
     }
 
     fn find_loop(&self) -> Option<Vec<CellP>> {
@@ -61,7 +130,7 @@ impl Map {
         let mut inside = false;
         let mut enclosed = 0;
 
-        let dbg = cfg!(test) || crate::Cli::global().verbose;
+        let dbg = crate::log::enabled(Level::Trace);
 
         let dx = self.0.dimensions().0;
         for (i, &p) in pipes.iter() {
@@ -74,15 +143,15 @@ impl Map {
             }
             if dbg {
                 if (p & ON_LOOP) != 0 {
-                    print!("{}", Self::graphic(*self.0.get(i).unwrap()));
+                    crate::vprint!("{}", self.0.get(i).unwrap().graphic());
                 } else if is_enclosed {
-                    print!("■");
+                    crate::vprint!("■");
                 } else {
-                    print!("·");
+                    crate::vprint!("·");
                 }
             }
             if dbg && (i.0 + 1 == dx) {
-                println!();
+                crate::vprintln!();
             }
         }
 
@@ -90,49 +159,9 @@ impl Map {
     }
 
     fn step(&self, p: CellP, d: Dir) -> Option<CellP> {
-        let has =
-            |px, py, what: fn(u8) -> bool| self.0.get((px, py)).map(|&x| what(x)).unwrap_or(false);
-
-        let (px, py) = p;
-        let check = |pipep: fn(u8) -> bool, qx, qy, pipeq: fn(u8) -> bool| {
-            (has(px, py, pipep) && has(qx, qy, pipeq)).then_some((qx, qy))
-        };
-
-        match d {
-            Dir::North => check(Self::pipe_north, px, py - 1, Self::pipe_south),
-            Dir::South => check(Self::pipe_south, px, py + 1, Self::pipe_north),
-            Dir::West => check(Self::pipe_west, px - 1, py, Self::pipe_east),
-            Dir::East => check(Self::pipe_east, px + 1, py, Self::pipe_west),
-        }
-    }
-
-    fn pipe_north(c: u8) -> bool {
-        b"JL|S".contains(&c)
-    }
-
-    fn pipe_south(c: u8) -> bool {
-        b"7F|S".contains(&c)
-    }
-
-    fn pipe_west(c: u8) -> bool {
-        b"J7-S".contains(&c)
-    }
-
-    fn pipe_east(c: u8) -> bool {
-        b"LF-S".contains(&c)
-    }
-
-    fn graphic(c: u8) -> char {
-        match c {
-            b'-' => '─',
-            b'|' => '│',
-            b'L' => '└',
-            b'F' => '┌',
-            b'7' => '┐',
-            b'J' => '┘',
-            b'.' => '·',
-            x => x as char,
-        }
+        let connects = |p, d| self.0.get(p).is_some_and(|x: &Pipe| x.connects(d));
+        let q = d.offset(p, 1);
+        (connects(p, d) && connects(q, d.opposite())).then_some(q)
     }
 }
 
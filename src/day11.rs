@@ -1,24 +1,37 @@
 use anyhow::Result;
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let (p1, p2) = run_impl(input)?;
+use crate::solution::Solution;
 
-    Ok(format!("{p1} {p2}"))
-}
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Vec<Vec<u8>>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Vec<u8>>> {
+        Ok(parse(input))
+    }
 
-fn run_impl(input: &str) -> Result<(i64, i64)> {
-    Ok((dist_sum(input, 2), dist_sum(input, 1_000_000)))
+    fn part1(&self, gm: &Vec<Vec<u8>>) -> Result<impl Display> {
+        Ok(dist_sum(gm, 2))
+    }
+
+    fn part2(&self, gm: &Vec<Vec<u8>>) -> Result<impl Display> {
+        Ok(dist_sum(gm, 1_000_000))
+    }
 }
 
-fn dist_sum(input: &str, empty_size: i64) -> i64 {
-    let gm: Vec<_> = input.lines().map(|line| line.as_bytes().to_vec()).collect();
+fn parse(input: &str) -> Vec<Vec<u8>> {
+    input.lines().map(|line| line.as_bytes().to_vec()).collect()
+}
 
+fn dist_sum(gm: &[Vec<u8>], empty_size: i64) -> i64 {
     let dx = gm.iter().map(|row| row.len()).max().unwrap_or(0);
 
     use std::iter::zip;
 
     let mut empty_cols = vec![true; dx];
-    for row in &gm {
+    for row in gm {
         for (empty, &b) in zip(empty_cols.iter_mut(), row.iter()) {
             if b != b'.' {
                 *empty = false;
@@ -29,7 +42,7 @@ fn dist_sum(input: &str, empty_size: i64) -> i64 {
     let mut galaxies = vec![];
 
     let mut y = 0;
-    for row in &gm {
+    for row in gm {
         let mut empty_row = true;
         let mut x = 0;
         for (&empty_col, &b) in zip(empty_cols.iter(), row.iter()) {
@@ -75,8 +88,9 @@ mod test {
 #...#.....
 ";
 
-        assert_eq!(dist_sum(sample, 2), 374);
-        assert_eq!(dist_sum(sample, 10), 1030);
-        assert_eq!(dist_sum(sample, 100), 8410);
+        let gm = parse(sample);
+        assert_eq!(dist_sum(&gm, 2), 374);
+        assert_eq!(dist_sum(&gm, 10), 1030);
+        assert_eq!(dist_sum(&gm, 100), 8410);
     }
 }
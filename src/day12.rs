@@ -1,28 +1,38 @@
 use std::collections::HashMap;
 
 use anyhow::{anyhow, Context, Result};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let p1 = proc(input, 1)?;
-    let p2 = proc(input, 5)?;
+use crate::solution::Solution;
+use crate::log::Level;
 
-    Ok(format!("{p1} {p2}"))
-}
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Vec<Pattern>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Pattern>> {
+        input.lines().map(Pattern::from).collect()
+    }
 
-fn proc(input: &str, n_copies: usize) -> Result<usize> {
-    let pats = input
-        .lines()
-        .map(Pattern::from)
-        .collect::<Result<Vec<_>>>()?;
+    fn part1(&self, pats: &Vec<Pattern>) -> Result<impl Display> {
+        Ok(proc(pats, 1))
+    }
+
+    fn part2(&self, pats: &Vec<Pattern>) -> Result<impl Display> {
+        Ok(proc(pats, 5))
+    }
+}
 
-    let dbg = cfg!(test) || crate::Cli::global().verbose;
+fn proc(pats: &[Pattern], n_copies: usize) -> usize {
+    let dbg = crate::log::enabled(Level::Debug);
     if dbg {
-        println!("\nusing {n_copies} copies");
+        crate::vprintln!("\nusing {n_copies} copies");
     }
 
-    Ok(pats.iter().enumerate().fold(0, |acc, (i, pat)| {
+    pats.iter().enumerate().fold(0, |acc, (i, pat)| {
         if dbg {
-            println!(
+            crate::vprintln!(
                 "{}/{} {} {:?}",
                 i,
                 pats.len(),
@@ -31,10 +41,10 @@ fn proc(input: &str, n_copies: usize) -> Result<usize> {
             );
         }
         acc + pat.num_arrg_unfolded(n_copies)
-    }))
+    })
 }
 
-struct Pattern {
+pub struct Pattern {
     pat: Vec<u8>,
     runs: Vec<usize>,
 }
@@ -1,25 +1,36 @@
 use anyhow::Result;
+use std::fmt::Display;
 use std::iter::zip;
 
-pub fn run(input: &str) -> Result<String> {
-    let v = parse_valley(input);
+use crate::grid::Grid;
+use crate::solution::Solution;
 
-    let p1 = v.iter().map(|m| m.reflection().unwrap()).sum::<usize>();
-    let p2 = v.iter().map(|m| m.smudged().unwrap()).sum::<usize>();
+pub struct Solver;
 
-    Ok(format!("{p1} {p2}"))
+impl Solution<'_> for Solver {
+    type Input = Vec<Mirror>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Mirror>> {
+        parse_valley(input)
+    }
+
+    fn part1(&self, v: &Vec<Mirror>) -> Result<impl Display> {
+        Ok(v.iter().map(|m| m.reflection().unwrap()).sum::<usize>())
+    }
+
+    fn part2(&self, v: &Vec<Mirror>) -> Result<impl Display> {
+        Ok(v.iter().map(|m| m.smudged().unwrap()).sum::<usize>())
+    }
 }
 
 #[derive(Debug, Clone)]
-struct Mirror(Vec<Vec<u8>>);
+pub struct Mirror(Grid<u8>);
 
 impl Mirror {
     #[allow(unused)]
     fn show(&self) {
-        self.0
-            .iter()
-            .for_each(|l| println!("{}", unsafe { std::str::from_utf8_unchecked(l) }));
-        println!();
+        self.0.show();
+        crate::vprintln!();
     }
 
     fn reflection(&self) -> Option<usize> {
@@ -30,17 +41,16 @@ impl Mirror {
         let org = self.reflection()?;
 
         let mut copy = self.clone();
-        for y in 0..copy.0.len() {
-            for x in 0..copy.0[y].len() {
-                let oldc = copy.0[y][x];
-                copy.0[y][x] = Self::flip(oldc);
+        for p in self.0.positions() {
+            let c = copy.0.get_mut(p).unwrap();
+            let oldc = *c;
+            *c = Self::flip(oldc);
 
-                if let Some(x) = copy.single_refl_other_than(org) {
-                    return Some(x);
-                }
-
-                copy.0[y][x] = oldc;
+            if let Some(x) = copy.single_refl_other_than(org) {
+                return Some(x);
             }
+
+            *copy.0.get_mut(p).unwrap() = oldc;
         }
 
         None
@@ -65,35 +75,60 @@ impl Mirror {
     }
 
     fn refls_vert(&self) -> impl Iterator<Item = usize> + '_ {
-        let my = self.0.len();
+        let my = self.0.dimensions().1;
         (1..my)
-            .filter(move |&y| zip((0..y).rev(), y..my).all(|(y0, y1)| self.0[y0] == self.0[y1]))
-            .map(|v| 100 * v)
+            .filter(move |&y| zip((0..y).rev(), y..my).all(|(y0, y1)| self.0.row(y0) == self.0.row(y1)))
+            .map(|v| 100 * v as usize)
     }
 
     fn refls_horz(&self) -> impl Iterator<Item = usize> + '_ {
-        let mx = self.0[0].len();
-        (1..mx).filter(move |&x| zip((0..x).rev(), x..mx).all(|(x0, x1)| self.eq_cols(x0, x1)))
+        let mx = self.0.dimensions().0;
+        (1..mx)
+            .filter(move |&x| zip((0..x).rev(), x..mx).all(|(x0, x1)| self.eq_cols(x0, x1)))
+            .map(|x| x as usize)
     }
 
-    fn eq_cols(&self, x0: usize, x1: usize) -> bool {
-        self.0.iter().all(|row| row[x0] == row[x1])
+    fn eq_cols(&self, x0: i32, x1: i32) -> bool {
+        self.0.column(x0).eq(self.0.column(x1))
     }
 }
 
-fn parse_valley(input: &str) -> Vec<Mirror> {
-    let mut mirrors = vec![];
+fn parse_valley(input: &str) -> Result<Vec<Mirror>> {
+    input
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| Grid::parse(block).map(Mirror))
+        .collect()
+}
 
-    for line in input.lines() {
-        if line.is_empty() {
-            mirrors.push(Mirror(vec![]));
-        } else {
-            if mirrors.is_empty() {
-                mirrors.push(Mirror(vec![]));
-            }
-            mirrors.last_mut().unwrap().0.push(line.as_bytes().to_vec());
-        }
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let sample = Solver
+            .parse(
+                "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#
+",
+            )
+            .unwrap();
+        assert_eq!(Solver.part1(&sample).unwrap().to_string(), "405");
+        assert_eq!(Solver.part2(&sample).unwrap().to_string(), "400");
     }
-
-    mirrors
 }
@@ -1,24 +1,42 @@
 use std::collections::HashSet;
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, Result};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    Ok(format!("{} {}", p1(input)?, p2(input)?))
+use crate::grid::Grid;
+use crate::solution::Solution;
+
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Platform;
+
+    fn parse(&self, input: &str) -> Result<Platform> {
+        Platform::parse(input)
+    }
+
+    fn part1(&self, p: &Platform) -> Result<impl Display> {
+        Ok(p1(p))
+    }
+
+    fn part2(&self, p: &Platform) -> Result<impl Display> {
+        p2(p)
+    }
 }
 
-fn p1(input: &str) -> Result<usize> {
-    let mut p = Platform::parse(input)?;
-    p.roll(Dir::North);
-    Ok(p.load())
+fn p1(p: &Platform) -> usize {
+    let mut p = p.clone();
+    p.roll_north();
+    p.load()
 }
 
-fn p2(input: &str) -> Result<usize> {
+fn p2(p: &Platform) -> Result<usize> {
     const STEPS: usize = 1_000_000_000;
-    load_after(input, STEPS)
+    load_after(p, STEPS)
 }
 
-fn load_after(input: &str, i: usize) -> Result<usize> {
-    let mut p = Platform::parse(input)?;
+fn load_after(p: &Platform, i: usize) -> Result<usize> {
+    let mut p = p.clone();
 
     let i = i.max(1);
 
@@ -27,7 +45,6 @@ fn load_after(input: &str, i: usize) -> Result<usize> {
     let mut v = vec![];
     let mut last_new = 0;
     loop {
-        use Dir::*;
         let l = p.load();
 
         v.push(l);
@@ -44,19 +61,14 @@ fn load_after(input: &str, i: usize) -> Result<usize> {
             last_new = v.len();
         }
 
-        for dir in [North, West, South, East].iter() {
-            p.roll(*dir);
-        }
+        p.spin_cycle();
     }
 
     let nrep = rfind_repeat(&v, 2).ok_or_else(|| anyhow!("can't find repeat"))?;
     let n0 = v.len() - nrep;
     let rpt = &v[n0..];
 
-    let dbg = cfg!(test) || crate::Cli::global().verbose;
-    if dbg {
-        println!("{n0},{nrep} {:?}", rpt);
-    }
+    crate::debug!("{n0},{nrep} {:?}", rpt);
 
     let j = (i - n0) % nrep;
 
@@ -76,104 +88,58 @@ fn rfind_repeat(v: &[usize], min_rpt: usize) -> Option<usize> {
     None
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-struct Platform {
-    dx: i32,
-    dy: i32,
-    m: Vec<u8>,
-}
-
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-enum Dir {
-    North,
-    West,
-    South,
-    East,
-}
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Platform(Grid<u8>);
 
 impl Platform {
     fn parse(input: &str) -> Result<Self> {
-        let (dx, v) = input.lines().try_fold((0, vec![]), |(dx, mut v), line| {
-            let b = line.as_bytes();
-            if v.is_empty() {
-                Ok((b.len(), b.to_vec()))
-            } else {
-                if b.len() != dx {
-                    bail!("invalid line {line}");
-                }
-                v.extend_from_slice(b);
-                Ok((dx, v))
-            }
-        })?;
-
-        Ok(Self {
-            dx: dx as i32,
-            dy: (v.len() / dx) as i32,
-            m: v,
-        })
+        Grid::parse(input).map(Self)
     }
 
     #[allow(unused)]
     fn show(&self) {
-        self.m
-            .chunks(self.dx as usize)
-            .for_each(|row| println!("{}", String::from_utf8_lossy(row)));
+        self.0.show();
     }
 
-    fn roll(&mut self, dir: Dir) {
-        match dir {
-            Dir::North => self.roll_impl((0, 0), (0, 1), self.dy, (1, 0), self.dx),
-            Dir::West => self.roll_impl((0, 0), (1, 0), self.dx, (0, 1), self.dy),
-            Dir::South => self.roll_impl((0, self.dy - 1), (0, -1), self.dy, (1, 0), self.dx),
-            Dir::East => self.roll_impl((self.dx - 1, 0), (-1, 0), self.dx, (0, 1), self.dy),
+    // roll_north moves the round rocks north until they hit a rock or the edge.
+    fn roll_north(&mut self) {
+        let (dx, dy) = self.0.dimensions();
+        for x in 0..dx {
+            let mut free = 0;
+            for y in 0..dy {
+                match self.0.get((x, y)) {
+                    Some(b'#') => free = y + 1,
+                    Some(b'O') => {
+                        if y != free {
+                            *self.0.get_mut((x, free)).unwrap() = b'O';
+                            *self.0.get_mut((x, y)).unwrap() = b'.';
+                        }
+                        free += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // spin_cycle rolls north, west, south and east, rotating the platform
+    // so that each of them in turn is north.
+    fn spin_cycle(&mut self) {
+        for _ in 0..4 {
+            self.roll_north();
+            self.0 = self.0.rotate_cw();
         }
     }
 
     // return load on the north
     fn load(&self) -> usize {
-        self.m
-            .chunks(self.dx as usize)
+        let dy = self.0.dimensions().1 as usize;
+        self.0
+            .rows()
             .enumerate()
-            .map(|(i, row)| ((self.dy as usize) - i) * row.iter().filter(|&&x| x == b'O').count())
+            .map(|(i, row)| (dy - i) * row.iter().filter(|&&x| x == b'O').count())
             .sum()
     }
-
-    fn roll_impl(
-        &mut self,
-        origin: (i32, i32),
-        dstep: (i32, i32),
-        nstep: i32,
-        dslice: (i32, i32),
-        nslice: i32,
-    ) {
-        let mut slc = origin;
-        for _ in 0..nslice {
-            self.roll_slice(slc, dstep, nstep);
-            slc.0 += dslice.0;
-            slc.1 += dslice.1;
-        }
-    }
-
-    fn roll_slice(&mut self, origin: (i32, i32), dstep: (i32, i32), nstep: i32) {
-        let mut p = origin.0 + origin.1 * self.dx;
-        let dstep = dstep.0 + dstep.1 * self.dx;
-        let mut free = p;
-        for _ in 0..nstep {
-            match self.m[p as usize] {
-                b'#' => free = p + dstep,
-                b'O' => {
-                    if p != free {
-                        self.m[free as usize] = b'O';
-                        self.m[p as usize] = b'.';
-                    }
-                    free += dstep;
-                }
-                _ => {}
-            }
-
-            p += dstep;
-        }
-    }
 }
 
 #[cfg(test)]
@@ -207,13 +173,15 @@ O..#.OO...
 #....#....
 ";
 
-        let mut sample = Platform::parse(sample_src).unwrap();
+        let sample = Platform::parse(sample_src).unwrap();
         let sample_rolld = Platform::parse(sample_rolld_src).unwrap();
 
-        sample.roll(Dir::North);
-        sample.show();
-        assert_eq!(sample, sample_rolld);
+        let mut rolld = sample.clone();
+        rolld.roll_north();
+        rolld.show();
+        assert_eq!(rolld, sample_rolld);
 
-        assert_eq!(p2(sample_src).ok(), Some(64));
+        assert_eq!(p1(&sample), 136);
+        assert_eq!(p2(&sample).ok(), Some(64));
     }
 }
@@ -1,10 +1,29 @@
 use anyhow::{anyhow, Result};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let p1 = input.trim().split(',').map(xhash).sum::<usize>();
-    let p2 = part2(input);
+use crate::solution::Solution;
+use crate::log::Level;
 
-    Ok(format!("{p1} {p2}"))
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Input = Vec<&'a str>;
+
+    fn parse(&self, input: &'a str) -> Result<Vec<&'a str>> {
+        Ok(parse(input))
+    }
+
+    fn part1(&self, ops: &Vec<&str>) -> Result<impl Display> {
+        Ok(ops.iter().copied().map(xhash).sum::<usize>())
+    }
+
+    fn part2(&self, ops: &Vec<&str>) -> Result<impl Display> {
+        part2(ops)
+    }
+}
+
+fn parse(input: &str) -> Vec<&str> {
+    input.trim().split(',').collect()
 }
 
 fn xhash(s: &str) -> usize {
@@ -13,19 +32,19 @@ fn xhash(s: &str) -> usize {
         .fold(0, |acc, &c| ((acc + c as usize) * 17) % 256)
 }
 
-fn part2(input: &str) -> usize {
+fn part2(ops: &[&str]) -> Result<usize> {
     let mut wall = Wall::new();
 
-    for op in input.trim().split(',') {
-        wall.handle(op).expect("invalid op");
+    for op in ops {
+        wall.handle(op)?;
     }
 
-    let dbg = cfg!(test) || crate::Cli::global().verbose;
+    let dbg = crate::log::enabled(Level::Trace);
     if dbg {
         wall.show();
     }
 
-    wall.power()
+    Ok(wall.power())
 }
 
 type TinyStr = tinystr::TinyAsciiStr<8>;
@@ -60,11 +79,11 @@ impl Wall {
 
     fn show(&self) {
         for (i, b) in self.0.iter().enumerate().filter(|(_, b)| !b.0.is_empty()) {
-            print!("Box {i}:");
+            crate::vprint!("Box {i}:");
             for l in &b.0 {
-                print!(" [{} {}]", l.label, l.focal_len);
+                crate::vprint!(" [{} {}]", l.label, l.focal_len);
             }
-            println!(" -> {}", b.power(i))
+            crate::vprintln!(" -> {}", b.power(i))
         }
     }
 }
@@ -122,6 +141,6 @@ mod test {
     #[test]
     fn it_works() {
         let sample = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-        assert_eq!(part2(sample), 145);
+        assert_eq!(part2(&parse(sample)).ok(), Some(145));
     }
 }
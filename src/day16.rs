@@ -1,57 +1,93 @@
 use crate::grid::{CellP, Dir, Grid};
-use anyhow::Result;
+use crate::solution::Solution;
+use crate::log::Level;
+use anyhow::{bail, Result};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let p1 = part1(input)?;
-    let p2 = part2(input)?;
-    Ok(format!("{p1} {p2}"))
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Grid<Tile>;
+
+    fn parse(&self, input: &str) -> Result<Grid<Tile>> {
+        Grid::parse_cells(input)
+    }
+
+    fn part1(&self, g: &Grid<Tile>) -> Result<impl Display> {
+        Ok(part1(g))
+    }
+
+    fn part2(&self, g: &Grid<Tile>) -> Result<impl Display> {
+        Ok(part2(g))
+    }
 }
 
-fn part1(input: &str) -> Result<usize> {
-    let g = Grid::parse(input)?;
-    Ok(count_energized(&g, (0, 0), (1, 0)))
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Tile {
+    Empty,
+    Mirror,     // '/'
+    BackMirror, // '\'
+    SplitterV,  // '|'
+    SplitterH,  // '-'
 }
 
-fn part2(input: &str) -> Result<usize> {
-    let g = Grid::parse(input)?;
+impl TryFrom<u8> for Tile {
+    type Error = anyhow::Error;
 
+    fn try_from(c: u8) -> Result<Self> {
+        Ok(match c {
+            b'.' => Tile::Empty,
+            b'/' => Tile::Mirror,
+            b'\\' => Tile::BackMirror,
+            b'|' => Tile::SplitterV,
+            b'-' => Tile::SplitterH,
+            _ => bail!("invalid tile {:?}", c as char),
+        })
+    }
+}
+
+fn part1(g: &Grid<Tile>) -> usize {
+    count_energized(g, (0, 0), Dir::East)
+}
+
+fn part2(g: &Grid<Tile>) -> usize {
     let (dx, dy) = g.dimensions();
-    let v = (0..dx).flat_map(|x| [((x, 0), (0, 1)), ((x, dy - 1), (0, -1))]);
-    let h = (0..dy).flat_map(|y| [((0, y), (1, 0)), ((dx - 1, y), (-1, 0))]);
-    Ok(v.chain(h)
-        .map(|(p, d)| count_energized(&g, p, d))
+    let v = (0..dx).flat_map(|x| [((x, 0), Dir::South), ((x, dy - 1), Dir::North)]);
+    let h = (0..dy).flat_map(|y| [((0, y), Dir::East), ((dx - 1, y), Dir::West)]);
+    v.chain(h)
+        .map(|(p, d)| count_energized(g, p, d))
         .max()
-        .unwrap())
+        .unwrap()
 }
 
-fn count_energized(grid: &Grid<u8>, p: CellP, dir: CellP) -> usize {
+fn count_energized(grid: &Grid<Tile>, p: CellP, dir: Dir) -> usize {
     let mut lights = Grid::new(grid.dimensions(), 0);
 
     fire(grid, &mut lights, p, dir);
 
-    let dbg = cfg!(test) || crate::Cli::global().verbose;
+    let dbg = crate::log::enabled(Level::Trace);
     if dbg {
-        println!("{};{}:", p.0, p.1);
+        crate::vprintln!("{};{}:", p.0, p.1);
         for r in lights.rows() {
             let s = r
                 .iter()
                 .map(|&x| if x > 0 { '#' } else { '·' })
                 .collect::<String>();
-            println!("  {s}");
+            crate::vprintln!("  {s}");
         }
     }
 
     lights.values().filter(|&l| *l != 0).count()
 }
 
-fn fire(grid: &Grid<u8>, lights: &mut Grid<u8>, mut p: CellP, mut d: CellP) {
+fn fire(grid: &Grid<Tile>, lights: &mut Grid<u8>, mut p: CellP, mut d: Dir) {
     loop {
         if !grid.is_inside(p) {
             return;
         }
 
         // mask corresponding the light direction
-        let light_mask = 1 << Dir::from_xy(d).unwrap().index();
+        let light_mask = 1 << d.index();
 
         let light = lights.get_mut(p).unwrap();
         if *light & light_mask != 0 {
@@ -59,33 +95,25 @@ fn fire(grid: &Grid<u8>, lights: &mut Grid<u8>, mut p: CellP, mut d: CellP) {
         }
         *light |= light_mask;
 
-        let step = |d0, d1| {
-            let q = (p.0 + d0, p.1 + d1);
-            (q, (d0, d1))
-        };
+        let step = |d: Dir| (d.offset(p, 1), d);
+        let horizontal = matches!(d, Dir::East | Dir::West);
 
         (p, d) = match grid.get(p).unwrap() {
-            b'/' => step(-d.1, -d.0),
-            b'\\' => step(d.1, d.0),
-            b'|' => {
-                if d.0 != 0 {
-                    let (px, dx) = step(0, -1); // up
-                    fire(grid, lights, px, dx);
-                    step(0, 1) // down
-                } else {
-                    step(d.0, d.1) // no change
-                }
+            Tile::Mirror if horizontal => step(d.turn_left()),
+            Tile::Mirror => step(d.turn_right()),
+            Tile::BackMirror if horizontal => step(d.turn_right()),
+            Tile::BackMirror => step(d.turn_left()),
+            Tile::SplitterV if horizontal => {
+                let (px, dx) = step(Dir::North);
+                fire(grid, lights, px, dx);
+                step(Dir::South)
             }
-            b'-' => {
-                if d.1 != 0 {
-                    let (px, dx) = step(-1, 0); // left
-                    fire(grid, lights, px, dx);
-                    step(1, 0) // right
-                } else {
-                    step(d.0, d.1) // no change
-                }
+            Tile::SplitterH if !horizontal => {
+                let (px, dx) = step(Dir::West);
+                fire(grid, lights, px, dx);
+                step(Dir::East)
             }
-            _ => step(d.0, d.1),
+            _ => step(d), // no change
         };
     }
 }
@@ -108,7 +136,8 @@ mod test {
 .|....-|.\
 ..//.|....
 ";
-        assert_eq!(part1(src).ok(), Some(46));
-        assert_eq!(part2(src).ok(), Some(51));
+        let g = Solver.parse(src).unwrap();
+        assert_eq!(part1(&g), 46);
+        assert_eq!(part2(&g), 51);
     }
 }
@@ -1,102 +1,68 @@
-use crate::grid::{CellP, Grid};
-use anyhow::Result;
-use pathfinding::prelude::astar;
+use crate::grid::search::Heading;
+use crate::grid::Grid;
+use crate::solution::Solution;
+use crate::log::Level;
+use anyhow::{bail, Result};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let p1 = part1(input)?;
-    let p2 = part2(input)?;
-    Ok(format!("{p1} {p2}"))
-}
+pub struct Solver;
 
-fn part1(input: &str) -> Result<u32> {
-    let g = Grid::parse(input)?;
-    Ok(min_heat_loss(&g, 0, 3))
-}
+// Input is the grid of heat losses of the blocks.
+impl Solution<'_> for Solver {
+    type Input = Grid<u8>;
+
+    fn parse(&self, input: &str) -> Result<Grid<u8>> {
+        Grid::parse_with(input, |c| match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            _ => bail!("invalid heat loss {:?}", c as char),
+        })
+    }
+
+    fn part1(&self, g: &Grid<u8>) -> Result<impl Display> {
+        Ok(min_heat_loss(g, 0, 3))
+    }
 
-fn part2(input: &str) -> Result<u32> {
-    let g = Grid::parse(input)?;
-    Ok(min_heat_loss(&g, 4, 10))
+    fn part2(&self, g: &Grid<u8>) -> Result<impl Display> {
+        Ok(min_heat_loss(g, 4, 10))
+    }
 }
 
 fn min_heat_loss(grid: &Grid<u8>, min_steps: usize, max_steps: usize) -> u32 {
     let (dx, dy) = grid.dimensions();
     let goal = (dx - 1, dy - 1);
-    let (v, c) = astar(
-        &Node::start(),
-        |&n| n.successors(grid, min_steps, max_steps),
-        |&n| n.distance(goal),
-        |&n| n.pos == goal,
-    )
-    .unwrap();
+    let (v, c) = grid
+        .astar(
+            [Heading::new((0, 0))],
+            |h, d, &loss| {
+                // the crucible turns only after min_steps and never goes back
+                let turns = h.dir.is_some_and(|hd| hd != d);
+                if h.dir == Some(d.opposite()) || (turns && h.run < min_steps) {
+                    return None;
+                }
+                let next = h.step(d);
+                (next.run <= max_steps).then_some((next, loss as u32))
+            },
+            |h| h.pos.0.abs_diff(goal.0) + h.pos.1.abs_diff(goal.1),
+            |h| h.pos == goal,
+        )
+        .unwrap();
 
-    let dbg = cfg!(test) || crate::Cli::global().verbose;
+    let dbg = crate::log::enabled(Level::Trace);
     if dbg {
+        // mark the path with letters after the digits
         let mut g2 = grid.clone();
-        for (i, n) in v.iter().enumerate() {
-            let c = b'a' + (i % 26) as u8;
-            *g2.get_mut(n.pos).unwrap() = c;
+        for (i, h) in v.iter().enumerate() {
+            *g2.get_mut(h.pos).unwrap() = 10 + (i % 26) as u8;
         }
-        g2.show();
+        g2.show_by(|&c| match c {
+            0..=9 => (b'0' + c) as char,
+            _ => (b'a' + c - 10) as char,
+        });
     }
 
     c
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct Node {
-    pos: CellP,
-
-    // last step direction and count
-    last_step_dir: CellP,
-    last_step_count: usize,
-}
-
-impl Node {
-    fn start() -> Node {
-        Node {
-            pos: (0, 0),
-            last_step_dir: (0, 0),
-            last_step_count: 10,
-        }
-    }
-
-    fn successors(&self, grid: &Grid<u8>, min_steps: usize, max_steps: usize) -> Vec<(Node, u32)> {
-        let dir_may_change = self.last_step_count >= min_steps;
-        const ADJ: &[CellP] = &[(-1, 0), (1, 0), (0, 1), (0, -1)];
-        ADJ.iter()
-            .filter(|&&d| d != back(self.last_step_dir))
-            .filter(move |&&d| dir_may_change || d == self.last_step_dir)
-            .filter_map(move |&d| {
-                let p = self.pos;
-                let pos = (p.0 + d.0, p.1 + d.1);
-                let last_step_count = if d == self.last_step_dir {
-                    self.last_step_count + 1
-                } else {
-                    1
-                };
-                (grid.is_inside(pos) && last_step_count <= max_steps).then(|| {
-                    (
-                        Node {
-                            pos,
-                            last_step_dir: d,
-                            last_step_count,
-                        },
-                        (grid.get(pos).unwrap() - b'0') as u32,
-                    )
-                })
-            })
-            .collect()
-    }
-
-    fn distance(&self, goal: CellP) -> u32 {
-        self.pos.0.abs_diff(goal.0) + self.pos.1.abs_diff(goal.1)
-    }
-}
-
-fn back(dir: CellP) -> CellP {
-    (-dir.0, -dir.1)
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
@@ -118,7 +84,8 @@ mod test {
 2546548887735
 4322674655533
 ";
-        assert_eq!(part1(src).ok(), Some(102));
-        assert_eq!(part2(src).ok(), Some(94));
+        let g = Solver.parse(src).unwrap();
+        assert_eq!(min_heat_loss(&g, 0, 3), 102);
+        assert_eq!(min_heat_loss(&g, 4, 10), 94);
     }
 }
@@ -1,28 +1,39 @@
 use anyhow::{anyhow, Result};
+use std::fmt::Display;
 
 use crate::grid::{Dir, Grid};
+use crate::solution::Solution;
+use crate::log::Level;
 
-pub fn run(input: &str) -> Result<String> {
-    Ok(format!("{} {}", part1(input)?, part2(input)?))
-}
+pub struct Solver;
 
-fn part1(input: &str) -> Result<usize> {
-    calc_sparse(input, Plan::parse)
-}
+impl Solution<'_> for Solver {
+    type Input = Vec<Plan>;
 
-fn part2(input: &str) -> Result<usize> {
-    calc_sparse(input, Plan::parse_fix)
-}
+    fn parse(&self, input: &str) -> Result<Vec<Plan>> {
+        input
+            .lines()
+            .map(|line| Plan::parse(line).ok_or_else(|| anyhow!("invalid line {line}")))
+            .collect()
+    }
 
-fn calc_sparse(input: &str, mut f: impl FnMut(&str) -> Option<Plan>) -> Result<usize> {
-    let plan = input
-        .lines()
-        .map(|line| f(line).ok_or_else(|| anyhow!("invalid line {line}")))
-        .collect::<Result<Vec<_>>>()?;
+    fn part1(&self, plan: &Vec<Plan>) -> Result<impl Display> {
+        Ok(calc_sparse(plan))
+    }
+
+    fn part2(&self, plan: &Vec<Plan>) -> Result<impl Display> {
+        let plan = plan
+            .iter()
+            .map(|p| p.fixed().ok_or_else(|| anyhow!("invalid color #{:06x}", p.rgb)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(calc_sparse(&plan))
+    }
+}
 
+fn calc_sparse(plan: &[Plan]) -> usize {
     let mut pts = vec![];
     let mut p = (0, 0);
-    for cmd in &plan {
+    for cmd in plan {
         pts.push(p);
         let d = cmd.dir.step(1);
         let d = (d.0 as i64 * cmd.len as i64, d.1 as i64 * cmd.len as i64);
@@ -33,7 +44,7 @@ fn calc_sparse(input: &str, mut f: impl FnMut(&str) -> Option<Plan>) -> Result<u
     let mut sparse = SparseGrid::from_control_points(b'.', pts.iter().copied());
 
     let mut p = (0, 0);
-    for cmd in &plan {
+    for cmd in plan {
         let d = cmd.dir.step(1);
         let d = (d.0 as i64 * cmd.len as i64, d.1 as i64 * cmd.len as i64);
 
@@ -56,13 +67,13 @@ fn calc_sparse(input: &str, mut f: impl FnMut(&str) -> Option<Plan>) -> Result<u
 
     sparse.flood_from_outside(b'+', |&b| b != b'#');
 
-    let dbg = cfg!(test) || crate::Cli::global().verbose;
+    let dbg = crate::log::enabled(Level::Trace);
     if dbg {
         sparse.grid.show();
-        println!();
+        crate::vprintln!();
     }
 
-    Ok(sparse.count_areas(|&b| b != b'+'))
+    sparse.count_areas(|&b| b != b'+')
 }
 
 struct SparseGrid {
@@ -146,7 +157,7 @@ impl SparseGrid {
     }
 }
 
-struct Plan {
+pub struct Plan {
     dir: Dir,
     len: usize,
     rgb: u32,
@@ -171,8 +182,9 @@ impl Plan {
         it.next().is_none().then_some(Plan { dir, len, rgb })
     }
 
-    fn parse_fix(s: &str) -> Option<Plan> {
-        let Plan { rgb, .. } = Self::parse(s)?;
+    // fixed reads the actual instruction from the color code
+    fn fixed(&self) -> Option<Plan> {
+        let rgb = self.rgb;
         let dir = match rgb & 0xF {
             0 => Dir::East,
             1 => Dir::South,
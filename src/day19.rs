@@ -1,32 +1,47 @@
+use std::fmt::Display;
 use std::{cmp::Ordering, collections::HashMap};
 
 use anyhow::{anyhow, Result};
 
-pub fn run(input: &str) -> Result<String> {
-    Ok(format!("{} {}", part1(input)?, part2(input)?))
-}
+use crate::solution::Solution;
+use crate::log::Level;
+
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = (Plan, Vec<Part>);
+
+    fn parse(&self, input: &str) -> Result<(Plan, Vec<Part>)> {
+        Plan::load(input)
+    }
 
-fn part1(input: &str) -> Result<usize> {
-    let (plan, parts) = Plan::load(input)?;
+    fn part1(&self, (plan, parts): &(Plan, Vec<Part>)) -> Result<impl Display> {
+        Ok(part1(plan, parts))
+    }
 
-    Ok(parts
+    fn part2(&self, (plan, _): &(Plan, Vec<Part>)) -> Result<impl Display> {
+        Ok(part2(plan))
+    }
+}
+
+fn part1(plan: &Plan, parts: &[Part]) -> usize {
+    parts
         .iter()
         .filter(|part| plan.is_accepted(part))
         .map(|part| part.sum_rating())
-        .sum())
+        .sum()
 }
 
-fn part2(input: &str) -> Result<usize> {
-    let (plan, _) = Plan::load(input)?;
-
-    Ok(plan.count_accepted(1, 4000))
+fn part2(plan: &Plan) -> usize {
+    plan.count_accepted(1, 4000)
 }
 
 type TinyStr = tinystr::TinyAsciiStr<4>;
-type Rating = u16;
+pub type Rating = u16;
 
+/// Workflows sorting parts.
 #[allow(unused)]
-struct Plan {
+pub struct Plan {
     wf: Vec<Workflow>,
 
     wf_index: HashMap<TinyStr, usize>,
@@ -35,7 +50,8 @@ struct Plan {
 }
 
 impl Plan {
-    fn load(input: &str) -> Result<(Plan, Vec<Part>)> {
+    /// Loads the workflows and the parts listed after them.
+    pub fn load(input: &str) -> Result<(Plan, Vec<Part>)> {
         let wf_index = input
             .lines()
             .take_while(|l| !l.is_empty())
@@ -100,7 +116,8 @@ impl Plan {
         ))
     }
 
-    fn is_accepted(&self, p: &Part) -> bool {
+    /// Reports if part `p` is accepted by the workflows.
+    pub fn is_accepted(&self, p: &Part) -> bool {
         self.is_accepted_impl(p) == Some(true)
     }
 
@@ -116,15 +133,16 @@ impl Plan {
         }
     }
 
-    fn count_accepted(&self, lo: Rating, hi: Rating) -> usize {
-        let dbg = cfg!(test) || crate::Cli::global().verbose;
+    /// Number of accepted parts with all ratings between `lo` and `hi` (inclusive).
+    pub fn count_accepted(&self, lo: Rating, hi: Rating) -> usize {
+        let dbg = crate::log::enabled(Level::Trace);
 
         let mut total = 0;
 
         let mut stack = vec![(self.start_index, PartSpace::range(lo, hi))];
         while let Some((i, ps)) = stack.pop() {
             if dbg {
-                println!("{}: {ps}", self.wf[i].label);
+                crate::vprintln!("{}: {ps}", self.wf[i].label);
             }
 
             self.wf[i].rules.iter().fold(ps, |acc, rule| {
@@ -268,10 +286,11 @@ impl Condition {
     }
 }
 
-struct Part([Rating; 4]);
+/// Part with its x, m, a and s ratings.
+pub struct Part(pub [Rating; 4]);
 
 impl Part {
-    fn parse(s: &str) -> Option<Part> {
+    pub fn parse(s: &str) -> Option<Part> {
         let mut p = Part([0; 4]);
         for frag in s.split(['{', ',', '}']).filter(|p| !p.is_empty()) {
             let (l, r) = frag.split_once('=')?;
@@ -280,7 +299,7 @@ impl Part {
         Some(p)
     }
 
-    fn sum_rating(&self) -> usize {
+    pub fn sum_rating(&self) -> usize {
         self.0.iter().map(|&i| i as usize).sum()
     }
 }
@@ -365,7 +384,8 @@ hdj{m>838:A,pv}
 "
         .trim();
 
-        assert_eq!(part1(sample).ok(), Some(19114));
-        assert_eq!(part2(sample).ok(), Some(167409079868000));
+        let (plan, parts) = Plan::load(sample).unwrap();
+        assert_eq!(part1(&plan, &parts), 19114);
+        assert_eq!(part2(&plan), 167409079868000);
     }
 }
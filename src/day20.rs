@@ -1,29 +1,39 @@
 use anyhow::{anyhow, bail, Result};
 use std::collections::{HashMap, VecDeque};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let dbg = crate::Cli::global().verbose;
-    if dbg {
-        Circuit::load(input)?.print_dot_graph();
-    }
+use crate::solution::Solution;
+use crate::log::Level;
 
-    let p1 = part1(input)?;
-    let p2 = part2(input)?;
-    Ok(format!("{p1} {p2}"))
-}
+pub struct Solver;
 
-fn part1(input: &str) -> Result<usize> {
-    Circuit::load(input).map(|c| c.button_pulses_hilo(1000))
-}
+impl Solution<'_> for Solver {
+    type Input = Circuit;
+
+    fn parse(&self, input: &str) -> Result<Circuit> {
+        let c = Circuit::load(input)?;
+
+        let dbg = crate::log::enabled(Level::Trace);
+        if dbg {
+            c.print_dot_graph();
+        }
+
+        Ok(c)
+    }
 
-fn part2(input: &str) -> Result<usize> {
-    Circuit::load(input).and_then(|c| c.button_presses_needed())
+    fn part1(&self, c: &Circuit) -> Result<impl Display> {
+        Ok(c.button_pulses_hilo(1000))
+    }
+
+    fn part2(&self, c: &Circuit) -> Result<impl Display> {
+        c.button_presses_needed()
+    }
 }
 
 type Name = u16;
 
 #[derive(Debug, Clone)]
-struct Circuit {
+pub struct Circuit {
     names: HashMap<String, Name>,
     links: Vec<Link>,
 
@@ -41,7 +51,7 @@ impl Circuit {
         let mut links = vec![];
 
         let mut add_name = |n: &str| {
-            if names.get(n).is_none() {
+            if !names.contains_key(n) {
                 names.insert(n.to_string(), names.len() as Name);
                 links.push(Link {
                     name: n.to_string(),
@@ -80,7 +90,7 @@ impl Circuit {
         }
 
         let state_len =
-            std::iter::zip(links.iter_mut(), vsrc.into_iter()).fold(0, |acc, (link, sources)| {
+            std::iter::zip(links.iter_mut(), vsrc).fold(0, |acc, (link, sources)| {
                 let state_len = match link.type_ {
                     LinkType::Special => 0,
                     LinkType::FlipFlop => 1,
@@ -148,14 +158,14 @@ impl Circuit {
             })
             .collect::<Vec<_>>();
 
-        let dbg = cfg!(test) || crate::Cli::global().verbose;
+        let dbg = crate::log::enabled(Level::Debug);
         if dbg {
             let names = final_inputs
                 .iter()
                 .map(|i| i.name.as_str())
                 .collect::<Vec<_>>()
                 .join(", ");
-            println!("looking for inputs {names}");
+            crate::vprintln!("looking for inputs {names}");
         }
 
         let mut state = self.new_state();
@@ -170,7 +180,7 @@ impl Circuit {
                 if inf.first_high.is_none() && conj_hi[inf.offset] {
                     inf.first_high = Some(npress);
                     if dbg {
-                        println!("  {}: {}", inf.name, npress);
+                        crate::vprintln!("  {}: {}", inf.name, npress);
                     }
                 }
                 acc && inf.first_high.is_some()
@@ -240,24 +250,24 @@ impl Circuit {
     }
 
     fn print_dot_graph(&self) {
-        println!("digraph G {{");
+        crate::vprintln!("digraph G {{");
         for l in &self.links {
             match l.type_ {
-                LinkType::FlipFlop => println!("  {} [shape=box];", l.name),
-                LinkType::Special => println!("  {} [shape=diamond];", l.name),
+                LinkType::FlipFlop => crate::vprintln!("  {} [shape=box];", l.name),
+                LinkType::Special => crate::vprintln!("  {} [shape=diamond];", l.name),
                 _ => {}
             }
             if !l.targets.is_empty() {
-                print!("  {} -> ", l.name);
+                crate::vprint!("  {} -> ", l.name);
                 let mut sep = "{";
                 for &t in &l.targets {
-                    print!("{} {}", sep, self.links[t as usize].name);
+                    crate::vprint!("{} {}", sep, self.links[t as usize].name);
                     sep = ",";
                 }
-                println!(" }};");
+                crate::vprintln!(" }};");
             }
         }
-        println!("}}\n");
+        crate::vprintln!("}}\n");
     }
 }
 
@@ -292,7 +302,7 @@ struct SourceLink<'a> {
 }
 
 impl<'a> SourceLink<'a> {
-    fn parse(line: &str) -> Option<SourceLink> {
+    fn parse(line: &str) -> Option<SourceLink<'_>> {
         let (l, r) = line.split_once(" -> ")?;
 
         let (type_, name) = match &l[0..1] {
@@ -332,6 +342,7 @@ broadcaster -> a
 &con -> output
 ";
 
+        let part1 = |src| Circuit::load(src).map(|c| c.button_pulses_hilo(1000));
         assert_eq!(part1(sample1).ok(), Some(32000000));
         assert_eq!(part1(sample2).ok(), Some(11687500));
     }
@@ -1,31 +1,60 @@
 use anyhow::{anyhow, bail, Result};
-use std::collections::VecDeque;
 
-use crate::grid::{CellP, Grid, STEPS};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    Ok(format!("{} {}", part1(input, 64)?, part2(input)?))
+use crate::grid::{CellP, Grid, DIRS};
+use crate::solution::Solution;
+use crate::log::Level;
+
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Grid<Plot>;
+
+    fn parse(&self, input: &str) -> Result<Grid<Plot>> {
+        Grid::parse_cells(input)
+    }
+
+    fn part1(&self, grid: &Grid<Plot>) -> Result<impl Display> {
+        part1(grid, 64)
+    }
+
+    fn part2(&self, grid: &Grid<Plot>) -> Result<impl Display> {
+        calc_smart(grid, 26501365)
+    }
 }
 
-fn part1(input: &str, nsteps: usize) -> Result<usize> {
-    let grid = Grid::parse(input)?;
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Plot {
+    Garden,
+    Rock,
+    Start,
+}
 
+impl TryFrom<u8> for Plot {
+    type Error = anyhow::Error;
+
+    fn try_from(c: u8) -> Result<Self> {
+        Ok(match c {
+            b'.' => Plot::Garden,
+            b'#' => Plot::Rock,
+            b'S' => Plot::Start,
+            _ => bail!("invalid plot {:?}", c as char),
+        })
+    }
+}
+
+fn part1(grid: &Grid<Plot>, nsteps: usize) -> Result<usize> {
     let start = grid
-        .find(&b'S')
+        .find(&Plot::Start)
         .ok_or_else(|| anyhow!("no starting position"))?;
 
-    let even_odd = fill(&grid, start, nsteps);
+    let even_odd = fill(grid, start, nsteps);
     Ok(even_odd[nsteps % 2])
 }
 
-fn part2(input: &str) -> Result<usize> {
-    calc_smart(input, 26501365)
-}
-
-fn calc_smart(input: &str, nsteps: usize) -> Result<usize> {
-    let grid = Grid::parse(input)?;
-
-    let start = verify_problem(&grid, nsteps)?;
+fn calc_smart(grid: &Grid<Plot>, nsteps: usize) -> Result<usize> {
+    let start = verify_problem(grid, nsteps)?;
 
     /*
 
@@ -115,11 +144,11 @@ fn calc_smart(input: &str, nsteps: usize) -> Result<usize> {
 
     let sel = if w % 4 == 3 { 1 } else { 0 };
 
-    // n, s, e, w
-    let corners = STEPS
+    // n, s, w, e
+    let corners = DIRS
         .iter()
-        .map(|(dx, dy)| ((1 + dx) * half_dim, (1 + dy) * half_dim))
-        .map(|p| fill(&grid, p, 2 * half_dim as usize)[0])
+        .map(|d| d.offset((half_dim, half_dim), half_dim))
+        .map(|p| fill(grid, p, 2 * half_dim as usize)[0])
         .sum::<usize>();
 
     let m = half_dim * 2;
@@ -128,75 +157,57 @@ fn calc_smart(input: &str, nsteps: usize) -> Result<usize> {
     // ne, nw, se, sw
     let outer_edges: usize = h * edges
         .iter()
-        .map(|&p| fill(&grid, p, (half_dim - 1) as usize)[0])
+        .map(|&p| fill(grid, p, (half_dim - 1) as usize)[0])
         .sum::<usize>();
 
     // NE, NW, SE, SW
     let inner_edges: usize = (h - 1)
         * edges
             .iter()
-            .map(|&p| fill(&grid, p, 3 * half_dim as usize)[1])
+            .map(|&p| fill(grid, p, 3 * half_dim as usize)[1])
             .sum::<usize>();
 
     let blks = [h * h, (h - 1) * (h - 1)];
     let (blocks_even, blocks_odd) = (blks[1 - sel], blks[sel]);
-    let fills = fill(&grid, start, (2 * grid_dim) as usize);
+    let fills = fill(grid, start, (2 * grid_dim) as usize);
     let (d_even, d_odd) = (fills[1 - sel], fills[sel]);
 
-    let dbg = cfg!(test) || crate::Cli::global().verbose;
-    if dbg {
-        println!("corners={corners} outer_edges={outer_edges} inner_edges={inner_edges}");
-        println!("blocks_even={blocks_even} d_even={d_even}");
-        println!("blocks_odd={blocks_odd} d_odd={d_odd}");
-    }
+    crate::debug!("corners={corners} outer_edges={outer_edges} inner_edges={inner_edges}");
+    crate::debug!("blocks_even={blocks_even} d_even={d_even}");
+    crate::debug!("blocks_odd={blocks_odd} d_odd={d_odd}");
 
     let inner_blocks = blocks_even * d_even + blocks_odd * d_odd;
 
     Ok(corners + outer_edges + inner_edges + inner_blocks)
 }
 
-fn fill(grid: &Grid<u8>, start: CellP, max_steps: usize) -> [usize; 2] {
+fn fill(grid: &Grid<Plot>, start: CellP, max_steps: usize) -> [usize; 2] {
     fill_ex(grid, start, max_steps).1
 }
 
-fn fill_ex(grid: &Grid<u8>, start: CellP, max_steps: usize) -> (Grid<Option<bool>>, [usize; 2]) {
-    let mut vis = Grid::new(grid.dimensions(), None);
-
-    *vis.get_mut(start).unwrap() = Some(false);
+fn fill_ex(grid: &Grid<Plot>, start: CellP, max_steps: usize) -> (Grid<Option<bool>>, [usize; 2]) {
+    let dist = grid.bfs([start], |&c| c != Plot::Rock);
 
-    let mut fifo = VecDeque::from([(0, start)]);
+    // parity of the cells reachable in max_steps
+    let vis = dist.map(|n| n.filter(|&n| n <= max_steps).map(|n| n % 2 != 0));
     let mut counts = [0, 0];
-    while let Some((n, p)) = fifo.pop_front() {
-        counts[n % 2] += 1;
-        if n < max_steps {
-            let n = n + 1;
-            for &d in STEPS {
-                let p = (p.0 + d.0, p.1 + d.1);
-                if *grid.get(p).unwrap_or(&b'#') != b'#' {
-                    let m = vis.get_mut(p).unwrap();
-                    if m.is_none() {
-                        *m = Some((n % 2) != 0);
-                        fifo.push_back((n, p));
-                    }
-                }
-            }
-        }
+    for &odd in vis.values().flatten() {
+        counts[odd as usize] += 1;
     }
 
-    let dbg = cfg!(test) || crate::Cli::global().verbose;
-    if dbg {
+    if crate::log::enabled(Level::Trace) {
         show_grid_vis(grid, &vis, "", Some(start));
     }
 
     (vis, counts)
 }
 
-fn show_grid_vis(grid: &Grid<u8>, vis: &Grid<Option<bool>>, prefix: &str, start: Option<CellP>) {
+fn show_grid_vis(grid: &Grid<Plot>, vis: &Grid<Option<bool>>, prefix: &str, start: Option<CellP>) {
     let counts = vis.values().filter_map(|&x| x).fold([0, 0], |mut acc, b| {
         acc[b as usize] += 1;
         acc
     });
-    println!(
+    crate::vprintln!(
         "{prefix}{}○:{} ●:{}",
         if prefix.is_empty() { "" } else { " " },
         counts[0],
@@ -211,7 +222,7 @@ fn show_grid_vis(grid: &Grid<u8>, vis: &Grid<Option<bool>>, prefix: &str, start:
                     'S'
                 } else {
                     match (g, v) {
-                        (b'#', _) => '▒',
+                        (Plot::Rock, _) => '▒',
                         (_, Some(false)) => '○',
                         (_, Some(true)) => '●',
                         _ => '·',
@@ -219,19 +230,19 @@ fn show_grid_vis(grid: &Grid<u8>, vis: &Grid<Option<bool>>, prefix: &str, start:
                 }
             })
             .collect::<String>();
-        println!("{}{s}", if prefix.is_empty() { "" } else { "  " });
+        crate::vprintln!("{}{s}", if prefix.is_empty() { "" } else { "  " });
     }
-    println!();
+    crate::vprintln!();
 }
 
-fn verify_problem(grid: &Grid<u8>, nsteps: usize) -> Result<CellP> {
+fn verify_problem(grid: &Grid<Plot>, nsteps: usize) -> Result<CellP> {
     let (dx, dy) = grid.dimensions();
 
     if dx != dy {
         bail!("grid must be rectangular");
     }
 
-    let start = grid.find(&b'S').unwrap();
+    let start = grid.find(&Plot::Start).unwrap();
 
     // start must be at center
     if start.0 * 2 + 1 != dx || start.1 * 2 + 1 != dy {
@@ -244,14 +255,14 @@ fn verify_problem(grid: &Grid<u8>, nsteps: usize) -> Result<CellP> {
 
     if (0..dx)
         .map(|x| grid.get((x, start.1)).unwrap())
-        .any(|&c| c == b'#')
+        .any(|&c| c == Plot::Rock)
     {
         bail!("start row is not empty");
     }
 
     if (0..dy)
         .map(|y| grid.get((start.0, y)).unwrap())
-        .any(|&c| c == b'#')
+        .any(|&c| c == Plot::Rock)
     {
         bail!("start column is not empty");
     }
@@ -268,24 +279,23 @@ fn verify_problem(grid: &Grid<u8>, nsteps: usize) -> Result<CellP> {
 mod test {
     use super::*;
 
-    fn calc_dumb(input: &str, nadd: usize, nsteps: usize) -> Result<usize> {
-        let grid = Grid::parse(input)?;
+    fn calc_dumb(grid: &Grid<Plot>, nadd: usize, nsteps: usize) -> Result<usize> {
         let (sx, sy) = grid.dimensions();
 
-        let rpt_grid = repeat_grid(&grid, nadd);
+        let m = nadd as i32;
+        let mm = 2 * m + 1;
+
+        let rpt_grid = grid.tile(mm, mm);
 
         let (dx, dy) = rpt_grid.dimensions();
         let start = (dx / 2, dy / 2);
 
-        if rpt_grid.get(start) != Some(&b'S') {
+        if rpt_grid.get(start) != Some(&Plot::Start) {
             bail!("start is not at the center");
         }
 
         let (vis, r) = fill_ex(&rpt_grid, start, nsteps);
 
-        let m = nadd as i32;
-        let mm = 2 * m + 1;
-
         let kind = |(x, y)| {
             let (dx, dy): (i32, i32) = (x - m, y - m);
             let n = dx.abs() + dy.abs();
@@ -320,7 +330,7 @@ mod test {
         for (x, y) in subgrid_positions {
             let k = kind((x, y));
             let topleft = (x * sx, y * sy);
-            let sub = subgrid(&vis, topleft, (sx, sy));
+            let sub = vis.view(topleft, (sx, sy)).unwrap();
 
             if let Some(last) = subgrids.get(&k) {
                 if last != &sub {
@@ -332,7 +342,7 @@ mod test {
         }
 
         for (k, sub) in subgrids.iter() {
-            show_grid_vis(&grid, sub, k, None);
+            show_grid_vis(grid, &sub.to_grid(), k, None);
         }
 
         Ok(r[nsteps % 2])
@@ -348,48 +358,16 @@ mod test {
         vx + vy
     }
 
-    fn subgrid<T>(grid: &Grid<T>, topleft: CellP, dim: CellP) -> Grid<T>
-    where
-        T: Clone,
-    {
-        let mut sub = Grid::new(dim, grid.get(topleft).unwrap().clone());
-        let src_rows = grid.rows().skip(topleft.1 as usize).take(dim.1 as usize);
-        for (sr, rr) in std::iter::zip(src_rows, sub.rows_mut()) {
-            let sr = &sr[topleft.0 as usize..][..dim.0 as usize];
-            rr.clone_from_slice(sr);
-        }
-
-        sub
-    }
-
-    fn repeat_grid(src: &Grid<u8>, nadd: usize) -> Grid<u8> {
-        let (dx, dy) = src.dimensions();
-
-        let m = 1 + 2 * (nadd as i32);
-        let mut rpt = Grid::new((dx * m, dy * m), 0);
-
-        let src = src.rows().map(|x| x.to_vec()).collect::<Vec<_>>();
-
-        use std::iter::zip;
-        for (sr, rr) in zip(src.iter().cycle(), rpt.rows_mut()) {
-            for (sc, rc) in zip(sr.iter().cycle(), rr.iter_mut()) {
-                *rc = *sc;
-            }
-        }
-
-        rpt
-    }
-
     fn run_checks(input: &str) {
-        let grid = Grid::parse(input).expect("grid load failed");
+        let grid = Solver.parse(input).expect("grid load failed");
 
         let dim = grid.dimensions().0 as usize;
 
         for nadd in 1..=5 {
             let nsteps = (nadd * 2 + 1) * dim / 2;
             println!("  {nsteps} steps");
-            let dumb = calc_dumb(input, nadd, nsteps).expect("dumb calc failed");
-            let smart = calc_smart(input, nsteps).expect("smart calc failed");
+            let dumb = calc_dumb(&grid, nadd, nsteps).expect("dumb calc failed");
+            let smart = calc_smart(&grid, nsteps).expect("smart calc failed");
 
             assert_eq!(dumb, smart);
         }
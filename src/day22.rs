@@ -2,14 +2,32 @@ use std::collections::{HashMap, HashSet};
 
 use anyhow::{anyhow, Result};
 
+use std::fmt::Display;
+
 use crate::quadmap;
+use crate::solution::Solution;
+
+pub struct Solver;
+
+// Input is the bricks in their settled positions.
+impl Solution<'_> for Solver {
+    type Input = (Vec<Brick>, FallResult);
+
+    fn parse(&self, input: &str) -> Result<(Vec<Brick>, FallResult)> {
+        settle(input)
+    }
 
-pub fn run(input: &str) -> Result<String> {
-    let (p1, p2) = sim(input)?;
-    Ok(format!("{p1} {p2}"))
+    fn part1(&self, (bricks, fr): &(Vec<Brick>, FallResult)) -> Result<impl Display> {
+        Ok(count_safe(bricks, fr))
+    }
+
+    fn part2(&self, (bricks, fr): &(Vec<Brick>, FallResult)) -> Result<impl Display> {
+        Ok(total_fallen(bricks, fr))
+    }
 }
 
-fn sim(input: &str) -> Result<(usize, usize)> {
+// settle parses the input and drops the bricks to their final positions
+fn settle(input: &str) -> Result<(Vec<Brick>, FallResult)> {
     let mut bricks = input
         .lines()
         .map(Brick::parse)
@@ -17,40 +35,24 @@ fn sim(input: &str) -> Result<(usize, usize)> {
 
     bricks.sort_unstable_by_key(Brick::bottom);
 
-    let FallResult {
-        supports,
-        supported_by,
-        n_fallen: _,
-    } = drop_bricks(&mut bricks);
+    let fr = drop_bricks(&mut bricks);
+
+    Ok((bricks, fr))
+}
+
+fn count_safe(bricks: &[Brick], fr: &FallResult) -> usize {
+    (0..bricks.len()).filter(|&i| fr.is_safe(i)).count()
+}
 
-    let r = (0..bricks.len())
+fn total_fallen(bricks: &[Brick], fr: &FallResult) -> usize {
+    (0..bricks.len())
+        .filter(|&i| !fr.is_safe(i))
         .map(|i| {
-            let safe = if let Some(bricks) = supports.get(&i) {
-                bricks
-                    .iter()
-                    .all(|i| supported_by.get(i).unwrap().len() > 1)
-            } else {
-                true
-            };
-
-            let n_fallen = if safe {
-                0
-            } else {
-                let mut bricks = bricks.clone();
-                bricks.remove(i);
-                drop_bricks(&mut bricks).n_fallen
-            };
-
-            n_fallen
+            let mut bricks = bricks.to_vec();
+            bricks.remove(i);
+            drop_bricks(&mut bricks).n_fallen
         })
-        .fold((0, 0), |(n_safe, total_fallen), n_fallen| {
-            (
-                n_safe + if n_fallen == 0 { 1 } else { 0 },
-                total_fallen + n_fallen,
-            )
-        });
-
-    Ok(r)
+        .sum()
 }
 
 fn drop_bricks(bricks: &mut [Brick]) -> FallResult {
@@ -94,12 +96,25 @@ fn drop_bricks(bricks: &mut [Brick]) -> FallResult {
     }
 }
 
-struct FallResult {
+pub struct FallResult {
     supports: HashMap<usize, HashSet<usize>>,
     supported_by: HashMap<usize, HashSet<usize>>,
     n_fallen: usize,
 }
 
+impl FallResult {
+    // is_safe reports if brick i can be removed without other bricks falling
+    fn is_safe(&self, i: usize) -> bool {
+        if let Some(bricks) = self.supports.get(&i) {
+            bricks
+                .iter()
+                .all(|i| self.supported_by.get(i).unwrap().len() > 1)
+        } else {
+            true
+        }
+    }
+}
+
 #[derive(Clone)]
 struct Vec3(i32, i32, i32);
 
@@ -119,7 +134,7 @@ impl Vec3 {
 }
 
 #[derive(Clone)]
-struct Brick {
+pub struct Brick {
     l: Vec3,
     r: Vec3,
 }
@@ -159,6 +174,8 @@ mod test {
 0,1,6~2,1,6
 1,1,8~1,1,9
 ";
-        assert_eq!(sim(sample).ok(), Some((5, 7)));
+        let (bricks, fr) = settle(sample).unwrap();
+        assert_eq!(count_safe(&bricks, &fr), 5);
+        assert_eq!(total_fallen(&bricks, &fr), 7);
     }
 }
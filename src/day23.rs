@@ -1,21 +1,32 @@
-use std::{collections::HashMap, time::Instant};
+use std::{collections::HashMap, fmt::Display, time::Instant};
 
 use anyhow::{anyhow, Result};
 
-use crate::grid::{CellP, Grid};
+use crate::grid::{CellP, Dir, Grid, DIRS};
+use crate::solution::Solution;
+use crate::log::Level;
 
-pub fn run(input: &str) -> Result<String> {
-    let (p1, p2) = problem(input)?;
-    Ok(format!("{p1} {p2}"))
-}
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Grid<u8>;
+
+    fn parse(&self, input: &str) -> Result<Grid<u8>> {
+        Grid::parse(input)
+    }
 
-fn problem(input: &str) -> Result<(usize, usize)> {
-    let grid = Grid::parse(input)?;
-    Ok((longest_path(&grid, true)?, longest_path(&grid, false)?))
+    fn part1(&self, grid: &Grid<u8>) -> Result<impl Display> {
+        longest_path(grid, true)
+    }
+
+    fn part2(&self, grid: &Grid<u8>) -> Result<impl Display> {
+        longest_path(grid, false)
+    }
 }
 
+
 fn longest_path(grid: &Grid<u8>, slippery: bool) -> Result<usize> {
-    let dbg = cfg!(test) || crate::Cli::global().verbose;
+    let dbg = crate::log::enabled(Level::Debug);
 
     let (dx, dy) = grid.dimensions();
 
@@ -38,7 +49,7 @@ fn longest_path(grid: &Grid<u8>, slippery: bool) -> Result<usize> {
         .map(|p| {
             (
                 pt_idx(p),
-                longest_paths_to_junctions(&grid, p, slippery)
+                longest_paths_to_junctions(grid, p, slippery)
                     .into_iter()
                     .map(|(q, n)| (pt_idx(q), n))
                     .collect::<Vec<_>>(),
@@ -47,7 +58,7 @@ fn longest_path(grid: &Grid<u8>, slippery: bool) -> Result<usize> {
         .collect::<HashMap<_, _>>();
 
     if dbg {
-        println!("graph {}", now.elapsed().as_secs_f32());
+        crate::vprintln!("graph {}", now.elapsed().as_secs_f32());
     }
 
     let start = pt_idx(start);
@@ -73,14 +84,14 @@ fn longest_path(grid: &Grid<u8>, slippery: bool) -> Result<usize> {
     }
 
     if dbg {
-        println!("finished {}", now.elapsed().as_secs_f32());
+        crate::vprintln!("finished {}", now.elapsed().as_secs_f32());
     }
 
     result.ok_or_else(|| anyhow!("path not found"))
 }
 
 fn longest_paths_to_junctions(grid: &Grid<u8>, from: CellP, slippery: bool) -> Vec<(CellP, usize)> {
-    let goal = grid_goal(&grid);
+    let goal = grid_goal(grid);
 
     let is_junc_goal = |p| p == goal || is_junction(grid, p);
 
@@ -119,12 +130,9 @@ fn longest_paths_to_junctions(grid: &Grid<u8>, from: CellP, slippery: bool) -> V
 
 fn is_junction(grid: &Grid<u8>, p: CellP) -> bool {
     grid.get(p).unwrap_or(&b'#') != &b'#' && {
-        let c = DIRS
-            .iter()
-            .filter(|(d, _)| {
-                let p = (p.0 + d.0, p.1 + d.1);
-                grid.get(p).unwrap_or(&b'#') != &b'#'
-            })
+        let c = grid
+            .neighbours4(p)
+            .filter(|&q| grid.get(q).unwrap() != &b'#')
             .count();
         c > 2
     }
@@ -136,34 +144,31 @@ fn grid_goal(grid: &Grid<u8>) -> CellP {
 }
 
 fn next_steps(grid: &Grid<u8>, p: CellP, slippery: bool) -> impl Iterator<Item = CellP> + '_ {
-    DIRS.iter()
-        .copied()
-        .enumerate()
-        .filter_map(move |(i, (d, c))| {
-            let q = (p.0 + d.0, p.1 + d.1);
-            let qc = *grid.get(q)?;
-            if slippery {
-                let pc = *grid.get(p)?;
-                let p_ok = pc == b'.' || pc == c;
-
-                let q_ok = qc != b'#' && qc != DIRS[opposite_dir(i)].1;
-
-                (p_ok && q_ok).then_some(q)
-            } else {
-                (qc != b'#').then_some(q)
-            }
-        })
+    DIRS.iter().filter_map(move |&d| {
+        let q = grid.neighbour(p, d)?;
+        let qc = *grid.get(q).unwrap();
+        if slippery {
+            let pc = *grid.get(p)?;
+            let p_ok = pc == b'.' || pc == slope(d);
+
+            let q_ok = qc != b'#' && qc != slope(d.opposite());
+
+            (p_ok && q_ok).then_some(q)
+        } else {
+            (qc != b'#').then_some(q)
+        }
+    })
 }
 
-const DIRS: [((i32, i32), u8); 4] = [
-    ((-1, 0), b'<'),
-    ((1, 0), b'>'),
-    ((0, -1), b'^'),
-    ((0, 1), b'v'),
-];
-
-fn opposite_dir(idx: usize) -> usize {
-    idx ^ 0x1
+// slope returns the tile of a slope going downhill towards d.
+fn slope(d: Dir) -> u8 {
+    match d {
+        Dir::West => b'<',
+        Dir::East => b'>',
+        Dir::North => b'^',
+        Dir::South => b'v',
+        _ => unreachable!("no diagonal slopes"),
+    }
 }
 
 #[cfg(test)]
@@ -197,6 +202,8 @@ mod test {
 #.....###...###...#...#
 #####################.#
 ";
-        assert_eq!(problem(sample).ok(), Some((94, 154)));
+        let grid = Grid::parse(sample).unwrap();
+        assert_eq!(longest_path(&grid, true).ok(), Some(94));
+        assert_eq!(longest_path(&grid, false).ok(), Some(154));
     }
 }
@@ -1,14 +1,28 @@
 use anyhow::{anyhow, bail, Result};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    let v = load_input(input)?;
-    let p1 = part1(&v);
-    let p2 = part2(&v).ok_or_else(|| anyhow!("can't find rock throw"))?;
-    Ok(format!("{p1} {p2}"))
+use crate::solution::Solution;
+
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Vec<Stone>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Stone>> {
+        load_input(input)
+    }
+
+    fn part1(&self, v: &Vec<Stone>) -> Result<impl Display> {
+        Ok(part1(v))
+    }
+
+    fn part2(&self, v: &Vec<Stone>) -> Result<impl Display> {
+        part2(v).ok_or_else(|| anyhow!("can't find rock throw"))
+    }
 }
 
 fn part1(v: &[Stone]) -> usize {
-    intersections_xy(&v, 200_000_000_000_000, 400_000_000_000_000)
+    intersections_xy(v, 200_000_000_000_000, 400_000_000_000_000)
 }
 
 fn part2(v: &[Stone]) -> Option<i64> {
@@ -26,7 +40,7 @@ type Coord = i64;
 type Vec3 = (Coord, Coord, Coord);
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct Stone {
+pub struct Stone {
     p: Vec3,
     v: Vec3,
 }
@@ -1,11 +1,30 @@
 use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
+use std::fmt::Display;
 
-pub fn run(input: &str) -> Result<String> {
-    println!("{}", input.lines().count());
-    println!("{:?}", load_input(input)?);
-    todo!()
+use crate::solution::{NoPuzzle, Solution};
+
+pub struct Solver;
+
+impl Solution<'_> for Solver {
+    type Input = Vec<Vec<usize>>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Vec<usize>>> {
+        crate::debug!("{}", input.lines().count());
+        load_input(input)
+    }
+
+    #[allow(unreachable_code)]
+    fn part1(&self, cxn: &Vec<Vec<usize>>) -> Result<impl Display> {
+        crate::debug!("{:?}", cxn);
+        todo!() as Result<usize>
+    }
+
+    // Day 25 has no second puzzle.
+    fn part2(&self, _: &Vec<Vec<usize>>) -> Result<impl Display> {
+        Err(anyhow!(NoPuzzle)) as Result<usize>
+    }
 }
 
 fn load_input(input: &str) -> Result<Vec<Vec<usize>>> {
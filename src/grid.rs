@@ -1,45 +1,53 @@
+//! Two dimensional grid of cells with directions and cell coordinates.
+
 use std::collections::HashSet;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+
+pub mod search;
 
-// Cell x and y coordinates
+/// Cell x and y coordinates
 pub type CellP = (i32, i32);
 
-#[allow(unused)]
+/// Unit steps to the north, south, west and east.
 pub const STEPS: &[CellP; 4] = &[(0, -1), (0, 1), (-1, 0), (1, 0)];
 
-#[allow(unused)]
+/// Cardinal directions in the same order as [`STEPS`].
 pub const DIRS: &[Dir; 4] = &[Dir::North, Dir::South, Dir::West, Dir::East];
 
-// Cardinal directions
-#[allow(unused)]
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+/// Cardinal directions followed by the diagonal ones.
+pub const DIRS8: &[Dir; 8] = &[
+    Dir::North,
+    Dir::South,
+    Dir::West,
+    Dir::East,
+    Dir::NorthWest,
+    Dir::NorthEast,
+    Dir::SouthWest,
+    Dir::SouthEast,
+];
+
+/// Cardinal and diagonal directions
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Dir {
     North,
     South,
     East,
     West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
 }
 
-#[allow(unused)]
 impl Dir {
+    /// Direction of a step along one axis or diagonal, or None for other or zero steps.
     pub fn from_xy((dx, dy): CellP) -> Option<Self> {
-        use std::cmp::Ordering::*;
-        if dx == 0 {
-            match dy.cmp(&0) {
-                Less => Some(Self::North),
-                Equal => None,
-                Greater => Some(Self::South),
-            }
-        } else if dy == 0 {
-            match dx.cmp(&0) {
-                Less => Some(Self::West),
-                Equal => None,
-                Greater => Some(Self::East),
-            }
-        } else {
-            None
+        if dx != 0 && dy != 0 && dx.abs() != dy.abs() {
+            return None;
         }
+        let unit = (dx.signum(), dy.signum());
+        DIRS8.iter().copied().find(|d| d.step(1) == unit)
     }
 
     pub fn opposite(self) -> Self {
@@ -48,37 +56,143 @@ impl Dir {
             Self::South => Self::North,
             Self::East => Self::West,
             Self::West => Self::East,
+            Self::NorthEast => Self::SouthWest,
+            Self::NorthWest => Self::SouthEast,
+            Self::SouthEast => Self::NorthWest,
+            Self::SouthWest => Self::NorthEast,
+        }
+    }
+
+    /// Direction after turning 90 degrees clockwise.
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::North => Self::East,
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
+            Self::NorthEast => Self::SouthEast,
+            Self::SouthEast => Self::SouthWest,
+            Self::SouthWest => Self::NorthWest,
+            Self::NorthWest => Self::NorthEast,
         }
     }
 
+    /// Direction after turning 90 degrees counterclockwise.
+    pub fn turn_left(self) -> Self {
+        self.turn_right().opposite()
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() >= 4
+    }
+
+    /// Unique index of the direction in `0..8`, the cardinal ones are in `0..4`.
     pub fn index(self) -> u8 {
         match self {
             Self::North => 0,
             Self::South => 1,
             Self::East => 2,
             Self::West => 3,
+            Self::NorthEast => 4,
+            Self::NorthWest => 5,
+            Self::SouthEast => 6,
+            Self::SouthWest => 7,
         }
     }
 
+    /// Step of length `x` in this direction.
     pub fn step(self, x: i32) -> CellP {
         match self {
             Self::North => (0, -x),
             Self::South => (0, x),
             Self::East => (x, 0),
             Self::West => (-x, 0),
+            Self::NorthEast => (x, -x),
+            Self::NorthWest => (-x, -x),
+            Self::SouthEast => (x, x),
+            Self::SouthWest => (-x, x),
         }
     }
+
+    /// Position `n` steps away from `p` in this direction.
+    pub fn offset(self, p: CellP, n: i32) -> CellP {
+        let (sx, sy) = self.step(n);
+        (p.0 + sx, p.1 + sy)
+    }
 }
 
-#[derive(Clone, Eq, PartialEq)]
+/// Rectangular grid of cells stored in row-major order.
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Grid<T> {
     dx: i32,
     dy: i32,
     m: Vec<T>,
 }
 
-#[allow(unused)]
 impl<T> Grid<T> {
+    /// Creates a grid of size `dims` with cells set to `f` of their position.
+    pub fn from_fn(dims: CellP, f: impl FnMut(CellP) -> T) -> Self {
+        let (dx, dy) = dims;
+        let m = (0..dy)
+            .flat_map(|y| (0..dx).map(move |x| (x, y)))
+            .map(f)
+            .collect();
+        Self { dx, dy, m }
+    }
+
+    /// Parses a grid from lines of equal length, converting bytes to cells using `f`.
+    ///
+    /// Leading empty lines are skipped. Errors report the line and column
+    /// of the offending byte, both starting at 1.
+    pub fn parse_with(input: &str, mut f: impl FnMut(u8) -> Result<T>) -> Result<Self> {
+        let (mut dx, mut dy) = (0, 0);
+        let mut m = Vec::new();
+        for (ln, line) in input.lines().enumerate() {
+            let bytes = line.as_bytes();
+            if dy == 0 {
+                if bytes.is_empty() {
+                    continue;
+                }
+                dx = bytes.len();
+            } else if bytes.len() != dx {
+                bail!(
+                    "line {}: length {} differs from the length {} of the first line",
+                    ln + 1,
+                    bytes.len(),
+                    dx
+                );
+            }
+            for (col, &b) in bytes.iter().enumerate() {
+                m.push(f(b).with_context(|| format!("line {} column {}", ln + 1, col + 1))?);
+            }
+            dy += 1;
+        }
+        Ok(Self {
+            dx: dx as i32,
+            dy,
+            m,
+        })
+    }
+
+    /// Parses a grid from lines of equal length, converting bytes to cells using [`TryFrom`].
+    pub fn parse_cells(input: &str) -> Result<Self>
+    where
+        T: TryFrom<u8>,
+        anyhow::Error: From<T::Error>,
+    {
+        Self::parse_with(input, |b| Ok(T::try_from(b)?))
+    }
+
+    /// Creates a grid of the same size with cells converted by `f`.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            dx: self.dx,
+            dy: self.dy,
+            m: self.m.iter().map(f).collect(),
+        }
+    }
+
+    /// Iterates over all cell positions in row-major order.
     pub fn positions(&self) -> impl Iterator<Item = CellP> + '_ {
         (0..self.dy).flat_map(|y| (0..self.dx).map(move |x| (x, y)))
     }
@@ -99,10 +213,43 @@ impl<T> Grid<T> {
         self.m.chunks_mut(self.dx as usize)
     }
 
+    pub fn row(&self, y: i32) -> Option<&[T]> {
+        (y >= 0 && y < self.dy).then(|| {
+            let s = (y * self.dx) as usize;
+            &self.m[s..s + self.dx as usize]
+        })
+    }
+
+    /// Iterates over the cells of column `x` from top to bottom,
+    /// yielding nothing if `x` is outside the grid.
+    pub fn column(&self, x: i32) -> impl Iterator<Item = &T> {
+        (0..self.dy).filter_map(move |y| self.get((x, y)))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.dx).map(|x| self.column(x))
+    }
+
+    /// Borrows the block of size `dims` starting at `topleft`,
+    /// or returns None if it does not fit in the grid.
+    pub fn view(&self, topleft: CellP, dims: CellP) -> Option<View<'_, T>> {
+        let (x0, y0) = topleft;
+        let (dx, dy) = dims;
+        let fits = x0 >= 0 && y0 >= 0 && dx >= 0 && dy >= 0;
+        (fits && x0 + dx <= self.dx && y0 + dy <= self.dy).then_some(View {
+            grid: self,
+            x0,
+            y0,
+            dx,
+            dy,
+        })
+    }
+
     pub fn as_slice(&self) -> &[T] {
         &self.m
     }
 
+    /// Width and height of the grid.
     pub fn dimensions(&self) -> (i32, i32) {
         (self.dx, self.dy)
     }
@@ -126,6 +273,22 @@ impl<T> Grid<T> {
         px >= 0 && px < self.dx && py >= 0 && py < self.dy
     }
 
+    /// Position next to `p` in direction `d`, if it is inside the grid.
+    pub fn neighbour(&self, p: CellP, d: Dir) -> Option<CellP> {
+        let q = d.offset(p, 1);
+        self.is_inside(q).then_some(q)
+    }
+
+    /// Positions north, south, west and east of `p` that are inside the grid.
+    pub fn neighbours4(&self, p: CellP) -> impl Iterator<Item = CellP> + '_ {
+        DIRS.iter().filter_map(move |&d| self.neighbour(p, d))
+    }
+
+    /// Positions around `p`, including the diagonal ones, that are inside the grid.
+    pub fn neighbours8(&self, p: CellP) -> impl Iterator<Item = CellP> + '_ {
+        DIRS8.iter().filter_map(move |&d| self.neighbour(p, d))
+    }
+
     pub fn get(&self, p: CellP) -> Option<&T> {
         self.to_index(p).map(|i| &self.m[i])
     }
@@ -134,16 +297,17 @@ impl<T> Grid<T> {
         self.to_index(p).map(|i| &mut self.m[i])
     }
 
+    /// Writes the grid to the log output, stderr by default, using `f` to display cells.
     pub fn show_by(&self, mut f: impl FnMut(&T) -> char) {
         for row in self.m.chunks(self.dx as usize) {
             let line: String = row.iter().map(&mut f).collect();
-            println!("{}", line);
+            crate::vprintln!("{}", line);
         }
     }
 }
 
-#[allow(unused)]
 impl<T: Clone> Grid<T> {
+    /// Creates a grid of size `dims` with all cells set to `v`.
     pub fn new(dims: CellP, v: T) -> Self {
         let (dx, dy) = dims;
         Self {
@@ -153,6 +317,7 @@ impl<T: Clone> Grid<T> {
         }
     }
 
+    /// Fills cells in the block between `p0` (inclusive) and `p1` (exclusive).
     pub fn fill_block(&mut self, p0: CellP, p1: CellP, fillc: T) {
         let x0 = p0.0.min(p1.0).max(0);
         let x1 = p0.0.max(p1.0).min(self.dx);
@@ -168,36 +333,114 @@ impl<T: Clone> Grid<T> {
     }
 }
 
-#[allow(unused)]
+impl<T: Clone> Grid<T> {
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Self {
+        Self::from_fn((self.dy, self.dx), |(x, y)| self.at((y, x)))
+    }
+
+    /// Rotates the grid clockwise, the top row becomes the right column.
+    pub fn rotate_cw(&self) -> Self {
+        Self::from_fn((self.dy, self.dx), |(x, y)| self.at((y, self.dy - 1 - x)))
+    }
+
+    /// Rotates the grid counterclockwise, the top row becomes the left column.
+    pub fn rotate_ccw(&self) -> Self {
+        Self::from_fn((self.dy, self.dx), |(x, y)| self.at((self.dx - 1 - y, x)))
+    }
+
+    /// Mirrors the grid left to right.
+    pub fn flip_h(&self) -> Self {
+        Self::from_fn((self.dx, self.dy), |(x, y)| self.at((self.dx - 1 - x, y)))
+    }
+
+    /// Mirrors the grid top to bottom.
+    pub fn flip_v(&self) -> Self {
+        Self::from_fn((self.dx, self.dy), |(x, y)| self.at((x, self.dy - 1 - y)))
+    }
+
+    /// Repeats the grid `nx` times horizontally and `ny` times vertically.
+    pub fn tile(&self, nx: i32, ny: i32) -> Self {
+        let dims = (self.dx * nx, self.dy * ny);
+        Self::from_fn(dims, |(x, y)| self.at((x % self.dx, y % self.dy)))
+    }
+
+    // at returns a copy of the cell at p, which must be inside the grid.
+    fn at(&self, p: CellP) -> T {
+        self.get(p).unwrap().clone()
+    }
+}
+
+/// Borrowed rectangular block of a [`Grid`], see [`Grid::view`].
+#[derive(Debug)]
+pub struct View<'a, T> {
+    grid: &'a Grid<T>,
+    x0: i32,
+    y0: i32,
+    dx: i32,
+    dy: i32,
+}
+
+// derive would require T: Clone
+impl<T> Clone for View<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for View<'_, T> {}
+
+impl<'a, T> View<'a, T> {
+    /// Width and height of the view.
+    pub fn dimensions(&self) -> (i32, i32) {
+        (self.dx, self.dy)
+    }
+
+    /// Cell at `p` relative to the top left corner of the view.
+    pub fn get(&self, p: CellP) -> Option<&'a T> {
+        let (px, py) = p;
+        let inside = px >= 0 && px < self.dx && py >= 0 && py < self.dy;
+        inside.then(|| self.grid.get((self.x0 + px, self.y0 + py)).unwrap())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let (x0, x1) = (self.x0 as usize, (self.x0 + self.dx) as usize);
+        let grid = self.grid;
+        (self.y0..self.y0 + self.dy).map(move |y| &grid.row(y).unwrap()[x0..x1])
+    }
+
+    /// Copies the view into a new grid.
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_fn((self.dx, self.dy), |p| self.get(p).unwrap().clone())
+    }
+}
+
+impl<T: PartialEq> PartialEq for View<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.dimensions() == other.dimensions() && self.rows().eq(other.rows())
+    }
+}
+
+impl<T: Eq> Eq for View<'_, T> {}
+
 impl Grid<u8> {
+    /// Parses a grid of bytes from lines of equal length.
     pub fn parse(input: &str) -> Result<Self> {
-        let (dx, m) = input
-            .lines()
-            .try_fold((0, Vec::new()), |(dx, mut v), line| {
-                let bytes = line.as_bytes();
-                if !v.is_empty() && dx != bytes.len() {
-                    bail!("invalid line");
-                }
-                v.extend_from_slice(bytes);
-                Ok((bytes.len(), v))
-            })?;
-        let dy = m.len() / dx;
-        Ok(Self {
-            dx: dx as i32,
-            dy: dy as i32,
-            m,
-        })
+        Self::parse_with(input, Ok)
     }
 
     pub fn show(&self) {
         for row in self.m.chunks(self.dx as usize) {
-            println!("{}", String::from_utf8_lossy(row));
+            crate::vprintln!("{}", String::from_utf8_lossy(row));
         }
     }
 }
 
-#[allow(unused)]
 impl<T: PartialEq> Grid<T> {
+    /// Position of the first cell equal to `what`.
     pub fn find(&self, what: &T) -> Option<CellP> {
         self.m
             .iter()
@@ -206,8 +449,9 @@ impl<T: PartialEq> Grid<T> {
     }
 }
 
-#[allow(unused)]
 impl<T: PartialEq + Clone> Grid<T> {
+    /// Sets cells to `value` starting at `start` and
+    /// spreading to adjacent cells as long as `pred` holds.
     pub fn flood<P>(&mut self, start: CellP, value: T, mut pred: P)
     where
         P: FnMut(&T) -> bool,
@@ -220,9 +464,8 @@ impl<T: PartialEq + Clone> Grid<T> {
         let mut visited = HashSet::new();
         while let Some(p) = stack.pop() {
             *self.get_mut(p).unwrap() = value.clone();
-            for &d in STEPS {
-                let q = (p.0 + d.0, p.1 + d.1);
-                if self.is_inside(q) && !visited.contains(&q) && pred(self.get(q).unwrap()) {
+            for q in self.neighbours4(p) {
+                if !visited.contains(&q) && pred(self.get(q).unwrap()) {
                     visited.insert(q);
                     stack.push(q);
                 }
@@ -230,3 +473,87 @@ impl<T: PartialEq + Clone> Grid<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    enum Cell {
+        Open,
+        Wall,
+    }
+
+    impl TryFrom<u8> for Cell {
+        type Error = anyhow::Error;
+
+        fn try_from(c: u8) -> Result<Self> {
+            Ok(match c {
+                b'.' => Cell::Open,
+                b'#' => Cell::Wall,
+                _ => bail!("invalid cell {:?}", c as char),
+            })
+        }
+    }
+
+    #[test]
+    fn it_works() {
+        let g = Grid::parse("\n12\n34\n").unwrap();
+        assert_eq!(g.dimensions(), (2, 2));
+        assert_eq!(g.get((1, 1)), Some(&b'4'));
+
+        let g = Grid::parse_with("12\n34\n", |b| Ok(b - b'0')).unwrap();
+        assert_eq!(g.as_slice(), [1, 2, 3, 4]);
+
+        let g: Grid<Cell> = Grid::parse_cells(".#\n#.").unwrap();
+        assert_eq!(g.find(&Cell::Wall), Some((1, 0)));
+
+        let err = Grid::<Cell>::parse_cells(".#\n#x").unwrap_err();
+        assert_eq!(format!("{:#}", err), "line 2 column 2: invalid cell 'x'");
+        let err = Grid::parse("..\n...").unwrap_err();
+        assert!(err.to_string().starts_with("line 2: length 3"));
+
+        let g = Grid::parse("abc\ndef\n").unwrap();
+        let text = |g: &Grid<u8>| {
+            let rows = g.rows().map(|r| String::from_utf8_lossy(r).into_owned());
+            rows.collect::<Vec<_>>().join("/")
+        };
+        assert_eq!(text(&g.transpose()), "ad/be/cf");
+        assert_eq!(text(&g.rotate_cw()), "da/eb/fc");
+        assert_eq!(text(&g.rotate_ccw()), "cf/be/ad");
+        assert_eq!(text(&g.flip_h()), "cba/fed");
+        assert_eq!(text(&g.flip_v()), "def/abc");
+        assert_eq!(text(&g.tile(2, 2)), "abcabc/defdef/abcabc/defdef");
+        assert_eq!(g.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), g);
+
+        assert_eq!(g.row(1), Some(&b"def"[..]));
+        assert!(g.column(1).eq(b"be"));
+        assert_eq!(g.columns().count(), 3);
+        assert_eq!(g.column(3).count(), 0);
+
+        let v = g.view((1, 0), (2, 2)).unwrap();
+        assert_eq!(v.get((1, 1)), Some(&b'f'));
+        assert_eq!(v.rows().collect::<Vec<_>>(), [b"bc", b"ef"]);
+        assert_eq!(text(&v.to_grid()), "bc/ef");
+        assert_eq!(v, g.tile(2, 1).view((4, 0), (2, 2)).unwrap());
+        assert!(g.view((2, 0), (2, 1)).is_none());
+
+        assert_eq!(g.neighbours4((0, 0)).collect::<Vec<_>>(), [(0, 1), (1, 0)]);
+        assert_eq!(g.neighbours8((0, 0)).count(), 3);
+        assert_eq!(g.neighbours8((1, 1)).count(), 5);
+        assert_eq!(g.neighbour((1, 1), Dir::NorthEast), Some((2, 0)));
+        assert_eq!(g.neighbour((1, 1), Dir::South), None);
+
+        for &d in DIRS8 {
+            assert_eq!(Dir::from_xy(d.step(3)), Some(d));
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_right().turn_right(), d.opposite());
+            assert_eq!(d.offset(d.opposite().offset((1, 1), 1), 1), (1, 1));
+        }
+        assert_eq!(Dir::North.turn_right(), Dir::East);
+        assert_eq!(Dir::NorthEast.turn_left(), Dir::NorthWest);
+        assert!(Dir::SouthWest.is_diagonal() && !Dir::West.is_diagonal());
+        assert_eq!(Dir::from_xy((2, 1)), None);
+        assert_eq!(Dir::from_xy((0, 0)), None);
+    }
+}
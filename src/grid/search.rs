@@ -0,0 +1,299 @@
+//! Searches over the cells of a [`Grid`]: breadth first distances,
+//! Dijkstra's and A* shortest paths and connected components.
+//!
+//! All searches move between the 4-connected neighbours of cells.
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use num::Zero;
+use rustc_hash::FxHashMap;
+
+use super::{CellP, Dir, Grid, DIRS};
+
+/// State of a search located at a cell of the grid.
+pub trait AtCell {
+    fn cell(&self) -> CellP;
+}
+
+impl AtCell for CellP {
+    fn cell(&self) -> CellP {
+        *self
+    }
+}
+
+/// Search state for walks restricted by how they reach a cell:
+/// the direction of the last step and the number of steps in a row taken in it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Heading {
+    pub pos: CellP,
+    pub dir: Option<Dir>,
+    pub run: usize,
+}
+
+impl Heading {
+    /// Starts a walk at `pos` with no step taken.
+    pub fn new(pos: CellP) -> Self {
+        Self {
+            pos,
+            dir: None,
+            run: 0,
+        }
+    }
+
+    /// Heading after a step towards `d`.
+    pub fn step(&self, d: Dir) -> Self {
+        let run = if self.dir == Some(d) { self.run + 1 } else { 1 };
+        Self {
+            pos: d.offset(self.pos, 1),
+            dir: Some(d),
+            run,
+        }
+    }
+}
+
+impl AtCell for Heading {
+    fn cell(&self) -> CellP {
+        self.pos
+    }
+}
+
+impl<T> Grid<T> {
+    /// Number of steps from the nearest of `sources` to every cell, moving only
+    /// to cells for which `passable` holds. Unreachable cells are None.
+    pub fn bfs(
+        &self,
+        sources: impl IntoIterator<Item = CellP>,
+        mut passable: impl FnMut(&T) -> bool,
+    ) -> Grid<Option<usize>> {
+        let mut dist = Grid::new(self.dimensions(), None);
+        let mut fifo = VecDeque::new();
+        for p in sources {
+            if let Some(d @ None) = dist.get_mut(p) {
+                *d = Some(0);
+                fifo.push_back((0, p));
+            }
+        }
+
+        while let Some((n, p)) = fifo.pop_front() {
+            for q in self.neighbours4(p) {
+                let d = dist.get_mut(q).unwrap();
+                if d.is_none() && passable(self.get(q).unwrap()) {
+                    *d = Some(n + 1);
+                    fifo.push_back((n + 1, q));
+                }
+            }
+        }
+
+        dist
+    }
+
+    /// Finds the cheapest path from one of `starts` to a state for which `is_goal` holds,
+    /// returning the states along the path and its cost.
+    ///
+    /// `step` is called with a state, a direction and the cell next to the state in that
+    /// direction, and returns the state on that cell and the cost of moving there,
+    /// or None if the move is not allowed. Costs must not be negative.
+    pub fn dijkstra<S, C>(
+        &self,
+        starts: impl IntoIterator<Item = S>,
+        step: impl FnMut(&S, Dir, &T) -> Option<(S, C)>,
+        is_goal: impl FnMut(&S) -> bool,
+    ) -> Option<(Vec<S>, C)>
+    where
+        S: AtCell + Clone + Eq + Hash,
+        C: Zero + Ord + Copy + Add<Output = C>,
+    {
+        self.astar(starts, step, |_| C::zero(), is_goal)
+    }
+
+    /// Like [`Grid::dijkstra`], but explores first the states that `heuristic` deems
+    /// closer to a goal. The heuristic must not overestimate the cost to the nearest
+    /// goal, eg. the Manhattan distance when moves cost at least 1.
+    pub fn astar<S, C>(
+        &self,
+        starts: impl IntoIterator<Item = S>,
+        mut step: impl FnMut(&S, Dir, &T) -> Option<(S, C)>,
+        mut heuristic: impl FnMut(&S) -> C,
+        mut is_goal: impl FnMut(&S) -> bool,
+    ) -> Option<(Vec<S>, C)>
+    where
+        S: AtCell + Clone + Eq + Hash,
+        C: Zero + Ord + Copy + Add<Output = C>,
+    {
+        // states with the index of the state they were reached from and their
+        // lowest known cost, and the index of each state
+        let mut nodes: Vec<(S, Option<usize>, C)> = vec![];
+        let mut index: FxHashMap<S, usize> = FxHashMap::default();
+        let mut heap = BinaryHeap::new();
+        for s in starts {
+            if let Entry::Vacant(e) = index.entry(s.clone()) {
+                e.insert(nodes.len());
+                heap.push(Reverse((heuristic(&s), C::zero(), nodes.len())));
+                nodes.push((s, None, C::zero()));
+            }
+        }
+
+        while let Some(Reverse((_, cost, i))) = heap.pop() {
+            if nodes[i].2 < cost {
+                continue; // reached at a lower cost since
+            }
+            let s = nodes[i].0.clone();
+            if is_goal(&s) {
+                let mut path = vec![];
+                let mut at = Some(i);
+                while let Some(j) = at {
+                    path.push(nodes[j].0.clone());
+                    at = nodes[j].1;
+                }
+                path.reverse();
+                return Some((path, cost));
+            }
+
+            for &d in DIRS {
+                let Some(q) = self.neighbour(s.cell(), d) else {
+                    continue;
+                };
+                let Some((t, c)) = step(&s, d, self.get(q).unwrap()) else {
+                    continue;
+                };
+                let cost = cost + c;
+                match index.entry(t) {
+                    Entry::Occupied(e) => {
+                        let j = *e.get();
+                        if cost < nodes[j].2 {
+                            nodes[j].1 = Some(i);
+                            nodes[j].2 = cost;
+                            heap.push(Reverse((cost + heuristic(&nodes[j].0), cost, j)));
+                        }
+                    }
+                    Entry::Vacant(e) => {
+                        let j = nodes.len();
+                        heap.push(Reverse((cost + heuristic(e.key()), cost, j)));
+                        nodes.push((e.key().clone(), Some(i), cost));
+                        e.insert(j);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Labels the connected components of the grid, where neighbouring cells
+    /// belong to the same component if `same` holds for them.
+    ///
+    /// Returns the label of every cell, counting from 0, and the number of components.
+    pub fn components(&self, mut same: impl FnMut(&T, &T) -> bool) -> (Grid<usize>, usize) {
+        let mut labels = Grid::new(self.dimensions(), usize::MAX);
+        let mut n = 0;
+        for p in self.positions() {
+            if *labels.get(p).unwrap() != usize::MAX {
+                continue;
+            }
+
+            *labels.get_mut(p).unwrap() = n;
+            let mut stack = vec![p];
+            while let Some(p) = stack.pop() {
+                let c = self.get(p).unwrap();
+                for q in self.neighbours4(p) {
+                    let l = labels.get_mut(q).unwrap();
+                    if *l == usize::MAX && same(c, self.get(q).unwrap()) {
+                        *l = n;
+                        stack.push(q);
+                    }
+                }
+            }
+            n += 1;
+        }
+
+        (labels, n)
+    }
+}
+
+impl Grid<Option<usize>> {
+    /// Reconstructs a shortest path from a source to `goal` in a distance field
+    /// returned by [`Grid::bfs`], both ends included.
+    pub fn path_to(&self, goal: CellP) -> Option<Vec<CellP>> {
+        let mut n = (*self.get(goal)?)?;
+        let mut path = vec![goal];
+        while n > 0 {
+            let p = *path.last().unwrap();
+            let q = self
+                .neighbours4(p)
+                .find(|&q| *self.get(q).unwrap() == Some(n - 1))?;
+            path.push(q);
+            n -= 1;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let g = Grid::parse(
+            "\
+S..#....
+.#.#.##.
+.#...#E.
+####.#..
+",
+        )
+        .unwrap();
+        let (start, goal) = (g.find(&b'S').unwrap(), g.find(&b'E').unwrap());
+
+        let dist = g.bfs([start], |&c| c != b'#');
+        assert_eq!(*dist.get(goal).unwrap(), Some(14));
+        assert_eq!(*dist.get((0, 2)).unwrap(), Some(2));
+        assert_eq!(*dist.get((3, 0)).unwrap(), None);
+        let path = dist.path_to(goal).unwrap();
+        assert_eq!((path[0], path.len()), (start, 15));
+        assert!(path
+            .windows(2)
+            .all(|w| g.neighbours4(w[0]).any(|q| q == w[1])));
+        assert!(dist.path_to((3, 3)).is_none());
+
+        let both = g.bfs([start, goal], |&c| c != b'#');
+        assert_eq!(*both.get((7, 0)).unwrap(), Some(3));
+
+        // walls cost 10, no turning back and at most 2 steps in a row in the same direction
+        let step = |h: &Heading, d: Dir, &c: &u8| {
+            if h.dir == Some(d.opposite()) {
+                return None;
+            }
+            let next = h.step(d);
+            let cost = if c == b'#' { 10 } else { 1 };
+            (next.run <= 2).then_some((next, cost))
+        };
+        let (path, cost) = g
+            .dijkstra([Heading::new(start)], step, |h| h.pos == goal)
+            .unwrap();
+        assert_eq!(cost, 19);
+        assert_eq!(path.first(), Some(&Heading::new(start)));
+        assert_eq!(path.last().unwrap().pos, goal);
+        assert!(path.windows(2).all(|w| w[1].run <= 2));
+
+        let manhattan = |h: &Heading| h.pos.0.abs_diff(goal.0) + h.pos.1.abs_diff(goal.1);
+        let (path, cost) = g
+            .astar([Heading::new(start)], step, manhattan, |h| h.pos == goal)
+            .unwrap();
+        assert_eq!(cost, 19);
+        assert_eq!(path.last().unwrap().pos, goal);
+
+        let unreachable = g.dijkstra([start], |&p, d, _| Some((d.offset(p, 1), 1)), |_| false);
+        assert!(unreachable.is_none());
+
+        let (labels, n) = g.components(|a, b| (*a == b'#') == (*b == b'#'));
+        assert_eq!(n, 4);
+        assert_eq!(labels.get(start), labels.get(goal));
+        assert_ne!(labels.get((3, 0)), labels.get((1, 1)));
+    }
+}
@@ -0,0 +1,162 @@
+//! Advent of Code 2023 solutions.
+//!
+//! The crate provides the solutions of each day through the [`Day`] registry
+//! keyed by year and day (see [`day`], [`year`] and [`years`]), the grid utilities
+//! used by the solutions ([`grid`] and [`quadmap`]), and [`InputSource`] to fetch
+//! and cache puzzle inputs.
+//!
+//! ```no_run
+//! let is = aoc23::InputSource::new();
+//! let input = is.get(5)?;
+//! let result = aoc23::day(2023, 5).unwrap().run(&input, None);
+//! for p in &result.parts {
+//!     println!("part {}: {:?}", p.part, p.answer);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+/// Year of the puzzles used when no year is specified.
+pub const DEFAULT_YEAR: u32 = 2023;
+
+pub mod answers;
+pub mod bench;
+pub mod config;
+pub mod grid;
+pub mod log;
+pub mod output;
+pub mod puzzle;
+pub mod quadmap;
+pub mod solution;
+pub mod submit;
+pub mod util;
+pub mod watch;
+
+pub use solution::{Day, DayResult, PartResult, Solution};
+pub use util::InputSource;
+
+// static_mod_items creates a static slice of `name` that contains the number
+// at the end of the name of each of the specified modules `m`, eg. 5 for `day05`,
+// with a reference to the item `mname` of the module as `mty`.
+//
+// It is visible in the modules declared after it, so the days of another year
+// can be added in a module, eg. `y2022`, containing the day modules and
+//
+//     static_mod_items!(pub(crate) DAYS, Solver as &dyn crate::Day, [day01, day02, day05]);
+//
+// and then registering `y2022::DAYS` in YEARS below. Days not solved yet are
+// simply left out.
+macro_rules! static_mod_items {
+    ( $vis:vis $name:ident, $mname:ident as $mty:ty, [ $( $m:ident ),* ] ) => {
+        $(
+            pub mod $m;
+        )*
+        $vis static $name: &[(usize, $mty)] = &[
+            $(
+                (crate::mod_number(stringify!($m)), &$m::$mname as $mty),
+            )*
+        ];
+    }
+}
+
+// mod_number returns the number at the end of a module name, eg. 5 for `day05`.
+const fn mod_number(name: &str) -> usize {
+    let b = name.as_bytes();
+    let mut n = 0;
+    let mut i = 0;
+    while i < b.len() {
+        n = if b[i].is_ascii_digit() {
+            n * 10 + (b[i] - b'0') as usize
+        } else {
+            0
+        };
+        i += 1;
+    }
+    n
+}
+
+static_mod_items!(
+    DAYS,
+    Solver as &dyn Day,
+    [
+        day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
+        day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25
+    ]
+);
+
+static YEARS: &[Year] = &[Year {
+    year: 2023,
+    days: DAYS,
+}];
+
+/// Solutions of the puzzles of a year.
+pub struct Year {
+    pub year: u32,
+    days: &'static [(usize, &'static dyn Day)],
+}
+
+impl Year {
+    /// Returns the solution of day `n` (starting at 1), if it exists.
+    pub fn day(&self, n: usize) -> Option<&'static dyn Day> {
+        self.days.iter().find(|&&(d, _)| d == n).map(|&(_, d)| d)
+    }
+
+    /// Iterates over the solved days with their numbers, in the order they were registered.
+    pub fn days(&self) -> impl Iterator<Item = (usize, &'static dyn Day)> {
+        self.days.iter().copied()
+    }
+}
+
+/// Iterates over the years having solutions, oldest first.
+pub fn years() -> impl Iterator<Item = &'static Year> {
+    YEARS.iter()
+}
+
+/// Returns the solutions of `year`, if any.
+pub fn year(year: u32) -> Option<&'static Year> {
+    YEARS.iter().find(|y| y.year == year)
+}
+
+/// Returns the solution of day `n` (starting at 1) of `year`, if it exists.
+pub fn day(year: u32, n: usize) -> Option<&'static dyn Day> {
+    self::year(year)?.day(n)
+}
+
+/// Parses a year given with four digits or as the last two digits, eg. `23`.
+pub fn parse_year(s: &str) -> anyhow::Result<u32> {
+    let y: u32 = s.parse().map_err(|_| anyhow::anyhow!("invalid year {s}"))?;
+    Ok(if y < 100 { 2000 + y } else { y })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        assert!(day(2023, 1).is_some());
+        assert!(day(2023, 26).is_none());
+        assert!(day(2015, 1).is_none());
+        assert_eq!(year(2023).unwrap().days().count(), 25);
+        assert!(years().any(|y| y.year == DEFAULT_YEAR));
+        assert_eq!(parse_year("23").unwrap(), 2023);
+        assert_eq!(parse_year("2022").unwrap(), 2022);
+        assert!(parse_year("x").is_err());
+
+        // a year with some days not solved yet, day 5 is the solution of 2023 day 1
+        static PARTIAL: Year = Year {
+            year: 2022,
+            days: &[
+                (1, &day02::Solver as &dyn Day),
+                (2, &day03::Solver as &dyn Day),
+                (5, &day01::Solver as &dyn Day),
+            ],
+        };
+        assert!(PARTIAL.day(3).is_none());
+        let r = PARTIAL.day(5).unwrap().run("1abc2\n", Some(1));
+        assert_eq!(r.parts[0].answer.as_deref().ok(), Some("12"));
+        let n: Vec<_> = PARTIAL.days().map(|(n, _)| n).collect();
+        assert_eq!(n, [1, 2, 5]);
+        assert_eq!(mod_number("day05"), 5);
+        assert_eq!(mod_number("day25"), 25);
+    }
+}
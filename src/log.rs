@@ -0,0 +1,232 @@
+//! Leveled log output of the solutions.
+//!
+//! The solutions log messages using [`info!`](crate::info), [`debug!`](crate::debug)
+//! and [`trace!`](crate::trace), and write longer dumps using [`vprint!`](crate::vprint)
+//! and [`vprintln!`](crate::vprintln) after checking [`enabled`].
+//!
+//! The level and output are set per thread using [`set_level`] and [`set_output`],
+//! so that days running in parallel don't interfere with each other.
+//! Output goes to stderr unless it is redirected.
+//! Unit tests log at [`Level::Debug`] by default.
+
+use anyhow::{anyhow, bail, Result};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// Log level, from the least to the most verbose.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "off" => Level::Off,
+            "info" => Level::Info,
+            "debug" => Level::Debug,
+            "trace" => Level::Trace,
+            _ => bail!("unknown log level {s}, use off, info, debug or trace"),
+        })
+    }
+}
+
+/// Log levels of the days.
+///
+/// Parsed from a comma separated list of a default level
+/// and levels of days, eg. `info,day17=trace`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    default: Level,
+    days: Vec<(usize, Level)>,
+}
+
+impl Filter {
+    /// Filter logging at `level` for all days.
+    pub fn new(level: Level) -> Self {
+        Self {
+            default: level,
+            days: vec![],
+        }
+    }
+
+    /// Log level of `day`.
+    pub fn level(&self, day: usize) -> Level {
+        self.days
+            .iter()
+            .rev()
+            .find(|(d, _)| *d == day)
+            .map_or(self.default, |(_, l)| *l)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut f = Filter::new(Level::Off);
+        for spec in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match spec.split_once('=') {
+                Some((day, level)) => {
+                    let day = day.strip_prefix("day").unwrap_or(day);
+                    let day = day
+                        .parse()
+                        .map_err(|_| anyhow!("invalid day in log filter {spec}"))?;
+                    f.days.push((day, level.parse()?));
+                }
+                None => f.default = spec.parse()?,
+            }
+        }
+        Ok(f)
+    }
+}
+
+thread_local! {
+    static LEVEL: Cell<Level> = const {
+        Cell::new(if cfg!(test) { Level::Debug } else { Level::Off })
+    };
+    static OUTPUT: RefCell<Option<Box<dyn Write>>> = RefCell::new(None);
+}
+
+/// Sets the log level of the current thread.
+pub fn set_level(level: Level) {
+    LEVEL.with(|l| l.set(level))
+}
+
+/// Log level of the current thread.
+pub fn level() -> Level {
+    LEVEL.with(|l| l.get())
+}
+
+/// Reports if messages at `level` are logged on the current thread.
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && level <= self::level()
+}
+
+/// Redirects log output of the current thread to `w`,
+/// or back to stderr if it is None. Returns the previous output.
+pub fn set_output(w: Option<Box<dyn Write>>) -> Option<Box<dyn Write>> {
+    OUTPUT.with(|out| out.replace(w))
+}
+
+/// Runs `f` and returns its result with the log output it produced.
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, String) {
+    let buf = Rc::new(RefCell::new(vec![]));
+    let prev = set_output(Some(Box::new(Capture(buf.clone()))));
+    let r = f();
+    set_output(prev);
+    let s = String::from_utf8_lossy(&buf.borrow()).into_owned();
+    (r, s)
+}
+
+struct Capture(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[doc(hidden)]
+pub fn write_fmt(args: fmt::Arguments) {
+    OUTPUT.with(|out| match &mut *out.borrow_mut() {
+        Some(w) => {
+            // log output is best effort
+            let _ = w.write_fmt(args);
+        }
+        None => eprint!("{}", args),
+    })
+}
+
+/// Writes log output like `print!`, regardless of the level.
+#[macro_export]
+macro_rules! vprint {
+    ($($arg:tt)*) => {
+        $crate::log::write_fmt(format_args!($($arg)*))
+    };
+}
+
+/// Writes log output like `println!`, regardless of the level.
+#[macro_export]
+macro_rules! vprintln {
+    () => {
+        $crate::log::write_fmt(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::log::write_fmt(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+/// Logs a line at `level`.
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($level) {
+            $crate::vprintln!($($arg)*)
+        }
+    };
+}
+
+/// Logs a line at the info level.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        $crate::log!($crate::log::Level::Info, $($arg)*)
+    };
+}
+
+/// Logs a line at the debug level.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        $crate::log!($crate::log::Level::Debug, $($arg)*)
+    };
+}
+
+/// Logs a line at the trace level.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        $crate::log!($crate::log::Level::Trace, $($arg)*)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let f: Filter = "info,day17=trace,3=off".parse().unwrap();
+        assert_eq!(f.level(1), Level::Info);
+        assert_eq!(f.level(17), Level::Trace);
+        assert_eq!(f.level(3), Level::Off);
+        assert!("day17=loud".parse::<Filter>().is_err());
+        assert!("dayx=info".parse::<Filter>().is_err());
+
+        set_level(Level::Debug);
+        let ((), s) = capture(|| {
+            crate::info!("a {}", 1);
+            crate::debug!("b");
+            crate::trace!("c");
+        });
+        assert_eq!(s, "a 1\nb\n");
+
+        set_level(Level::Off);
+        let ((), s) = capture(|| crate::info!("a"));
+        assert_eq!(s, "");
+    }
+}
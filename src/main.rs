@@ -1,89 +1,960 @@
-use anyhow::Result;
-use clap::Parser;
-use once_cell::sync::OnceCell;
-use std::collections::HashSet;
-use std::time::{Duration, Instant};
-
-const AOC_YEAR: u32 = 23;
-
-mod grid;
-mod quadmap;
-
-// static_mod_funcs creates a static slice of `name` that
-// contains the methods `mname` as `mty` in the specified modules `m`.
-macro_rules! static_mod_funcs {
-    ( $name:ident, $mname:ident as $mty:ty, [ $( $m:ident ),* ] ) => {
-        $(
-            mod $m;
-        )*
-        static $name: &[$mty] = &[
-            $(
-                ($m::$mname as $mty),
-            )*
-        ];
-    }
-}
-
-static_mod_funcs!(
-    DAY_FNS,
-    run as DayFunc,
-    [
-        day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
-        day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25
-    ]
-);
-
-mod util;
-use util::InputSource;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aoc23::answers::{AnswerStore, Check};
+use aoc23::bench::{self, DayBench, History, Report, Stats};
+use aoc23::config::{Config, Profile, DEFAULT_PROFILE};
+use aoc23::log::{Filter, Level};
+use aoc23::output::{Format, Record, RecordWriter};
+use aoc23::submit::{self, Attempt, Attempts, Verdict};
+use aoc23::watch::Watcher;
+use aoc23::{Day, DayResult, InputSource};
 
 #[derive(Debug, Parser)]
 struct Cli {
+    /// Log output of the days at debug level
     #[arg(short, long)]
     verbose: bool,
 
+    /// Log output of the days as set by SPEC, eg. --log info,day17=trace, overriding -v
+    #[arg(long, value_name = "SPEC")]
+    log: Option<Filter>,
+
+    /// Write log output to FILE instead of stderr
+    #[arg(long, value_name = "FILE")]
+    log_file: Option<PathBuf>,
+
     #[arg(short, long)]
     all: bool,
 
+    /// Year of the puzzles, eg. 2023 or 23
+    #[arg(short, long, value_parser = parse_year, default_value_t = aoc23::DEFAULT_YEAR)]
+    year: u32,
+
+    /// Run only the specified part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Read the input of the selected day from a file
+    #[arg(short, long, conflicts_with_all = ["stdin", "all"])]
+    input: Option<PathBuf>,
+
+    /// Read the input of the selected day from stdin
+    #[arg(long, conflicts_with = "all")]
+    stdin: bool,
+
+    /// Run the days on example N of the puzzle description instead of the input
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..),
+          conflicts_with_all = ["input", "stdin", "check", "record"])]
+    example: Option<u32>,
+
+    /// Check answers against the recorded ones
+    #[arg(long, conflicts_with_all = ["input", "stdin"])]
+    check: bool,
+
+    /// Record answers as the expected ones
+    #[arg(long, conflicts_with_all = ["input", "stdin"])]
+    record: bool,
+
+    /// Benchmark the days with N runs after a warmup
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..),
+          conflicts_with_all = ["check", "record"])]
+    bench: Option<u32>,
+
+    /// Write benchmark results as JSON to FILE
+    #[arg(long, value_name = "FILE", requires = "bench")]
+    bench_out: Option<PathBuf>,
+
+    /// Compare benchmark results with a git revision from the history or a JSON file
+    #[arg(long, value_name = "REV|FILE", requires = "bench")]
+    compare: Option<String>,
+
+    /// Slowdown in percent reported as regression by --compare
+    #[arg(long, value_name = "PCT", default_value_t = 10.0, requires = "compare")]
+    threshold: f64,
+
+    /// Output format: text, json, csv or tap
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "text",
+        conflicts_with = "bench"
+    )]
+    format: Format,
+
+    /// Run N days in parallel, or as many as there are CPUs with 0
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        conflicts_with = "bench"
+    )]
+    jobs: usize,
+
+    /// Re-run the days when their inputs change, and rebuild when the sources change
+    #[arg(long, conflicts_with_all = ["stdin", "check", "record", "bench", "timeout"])]
+    watch: bool,
+
+    /// Stop waiting for a day after SECS seconds
+    #[arg(long, value_name = "SECS", value_parser = parse_secs, conflicts_with = "bench")]
+    timeout: Option<Duration>,
+
+    /// Use only cached inputs, never fetch them
+    #[arg(long)]
+    offline: bool,
+
+    /// Fetch inputs from URL instead of the advent of code website, overriding AOC<YY>_BASE_URL
+    #[arg(long, value_name = "URL")]
+    base_url: Option<String>,
+
+    /// Folder of cached inputs and other data of the selected year, overriding AOC<YY>_CACHE
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// Use the session and settings of profile NAME from the config file
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Read profiles from FILE, overriding AOC_CONFIG
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
     days: Vec<usize>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-impl Cli {
-    #[allow(unused)]
-    pub fn global() -> &'static Cli {
-        CLI_INSTANCE.get().expect("CLI is not initialized")
-    }
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Manage the cached inputs
+    #[command(subcommand)]
+    Cache(CacheCommand),
+
+    /// Submit the answer of a part computed from the input
+    Submit {
+        day: usize,
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+    },
+
+    /// Check the session of the profile against the website
+    Whoami,
 }
 
-static CLI_INSTANCE: OnceCell<Cli> = OnceCell::new();
+#[derive(Debug, Subcommand)]
+enum CacheCommand {
+    /// List the cached inputs
+    List,
+    /// Print the cached input of a day
+    Show { day: usize },
+    /// Check the cached inputs for error pages and truncation
+    Verify,
+    /// Remove the cached input of a day, or all cached inputs
+    Purge { day: Option<usize> },
+}
 
-fn main() -> anyhow::Result<()> {
-    let is = InputSource::new()?;
+// RunCtx holds the settings shared by the days running in parallel.
+struct RunCtx<'a> {
+    cli: &'a Cli,
+    is: &'a InputSource,
+    input_override: Option<&'a str>,
+    store: Option<&'a AnswerStore>,
+
+    filter: Filter,
+    log_file: Option<(PathBuf, File)>,
+
+    // days are running in parallel, so their log output must be
+    // captured and written along with their results
+    parallel: bool,
+}
+
+// DayOutput is the outcome of running a day.
+struct DayOutput {
+    i: usize,
+    dr: DayResult,
+    checks: Vec<Option<Check>>,
+    log: Option<PathBuf>,
+    captured: String,
+}
 
+fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    let dfs = get_day_funcs(&cli);
+    let profile = load_profile(&cli)?;
+    let mut is = InputSource::for_year(cli.year);
+    // named profiles keep their inputs apart, also in the cache dir of the command line
+    if let Some(dir) = &cli.cache_dir {
+        is = is.with_cache_dir(dir);
+    }
+    let mut is = is.with_profile(&profile).offline(cli.offline);
+    if let Some(url) = &cli.base_url {
+        is = is.with_base_url(url);
+    }
+
+    match &cli.command {
+        Some(Command::Cache(cmd)) => return run_cache(&is, cmd),
+        Some(Command::Submit { day, part }) => return run_submit(&cli, &is, *day, *part),
+        Some(Command::Whoami) => {
+            let name = is.whoami()?;
+            println!("Profile {}: logged in as {}", is.profile(), name);
+            return Ok(());
+        }
+        None => {}
+    }
+
+    let days = get_days(&cli)?;
+
+    let input_override = read_input_override(&cli)?;
+    if input_override.is_some() && days.len() != 1 {
+        bail!("--input and --stdin need exactly one day");
+    }
+
+    let store = if cli.check || cli.record {
+        Some(answer_store(&is)?)
+    } else {
+        None
+    };
+
+    if let Some(runs) = cli.bench {
+        return run_bench(&cli, &is, &days, input_override.as_deref(), runs);
+    }
 
-    CLI_INSTANCE.set(cli).unwrap();
+    if cli.watch {
+        if cli.format != Format::Text {
+            bail!("--watch supports only the text format");
+        }
+        return run_watch(&cli, &is, &days);
+    }
+
+    let mut writer = match cli.format {
+        Format::Text => None,
+        format => Some(RecordWriter::new(std::io::stdout(), format)?),
+    };
+
+    let jobs = match cli.jobs {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+
+    let log_file = match &cli.log_file {
+        Some(path) => {
+            let f = File::create(path)
+                .with_context(|| format!("error creating log file {:?}", path))?;
+            Some((path.clone(), f))
+        }
+        None => None,
+    };
 
-    for (i, f) in dfs {
-        let r = is.get(i);
-        let now = Instant::now();
-        let r = r.and_then(|s| f(&s));
-        print!("Day {:2}: ", i);
-        match r {
-            Ok(result) => println!("{}  ({})", result, fmt_duration(now.elapsed())),
-            Err(e) => {
+    let ctx = RunCtx {
+        cli: &cli,
+        is: &is,
+        input_override: input_override.as_deref(),
+        store: store.as_ref(),
+        filter: log_filter(&cli),
+        log_file,
+        parallel: jobs > 1,
+    };
+
+    let mut n_failed = 0;
+    run_ordered(
+        &days,
+        jobs,
+        |&(i, day)| run_day(&ctx, i, day),
+        |out| {
+            let out = out?;
+            let failed = !out.dr.is_ok()
+                || out
+                    .checks
+                    .iter()
+                    .any(|c| matches!(c, Some(Check::Fail { .. })));
+            if failed {
+                n_failed += 1;
+            }
+
+            match &mut writer {
+                Some(w) => {
+                    for r in Record::from_day(out.i, &out.dr, &out.checks, out.log) {
+                        w.write(&r)?;
+                    }
+                }
+                None => print_day(out.i, &out.dr, &out.checks),
+            }
+            if !out.captured.is_empty() {
+                match &ctx.log_file {
+                    Some((path, f)) => (&*f)
+                        .write_all(out.captured.as_bytes())
+                        .with_context(|| format!("error writing log file {:?}", path))?,
+                    None => eprint!("{}", out.captured),
+                }
+            }
+            Ok(())
+        },
+    )?;
+
+    if let Some(w) = writer {
+        w.finish()?;
+    }
+
+    if n_failed > 0 {
+        bail!("{} day(s) failed", n_failed);
+    }
+
+    Ok(())
+}
+
+// run_ordered runs f on the items using `jobs` threads,
+// and passes the results to emit in the order of the items.
+fn run_ordered<I: Sync, T: Send>(
+    items: &[I],
+    jobs: usize,
+    f: impl Fn(&I) -> T + Sync,
+    mut emit: impl FnMut(T) -> Result<()>,
+) -> Result<()> {
+    if jobs <= 1 {
+        return items.iter().try_for_each(|x| emit(f(x)));
+    }
+
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+    std::thread::scope(|s| {
+        for _ in 0..jobs.min(items.len()) {
+            let tx = tx.clone();
+            let (next, f) = (&next, &f);
+            s.spawn(move || loop {
+                let k = next.fetch_add(1, Ordering::Relaxed);
+                if k >= items.len() || tx.send((k, f(&items[k]))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut pending = BTreeMap::new();
+        let mut want = 0;
+        for (k, t) in rx {
+            pending.insert(k, t);
+            while let Some(t) = pending.remove(&want) {
+                emit(t)?;
+                want += 1;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn run_day(ctx: &RunCtx, i: usize, day: &'static dyn Day) -> Result<DayOutput> {
+    let cli = ctx.cli;
+    let part = cli.part;
+
+    let input = match ctx.input_override {
+        Some(input) => Ok(input.to_string()),
+        None => get_input(cli, ctx.is, i),
+    };
+
+    // Log output goes to the log file, or for structured output to a file per day.
+    let level = ctx.filter.level(i);
+    let (log, log_out) = match &ctx.log_file {
+        _ if level == Level::Off => (None, None),
+        Some((path, _)) if ctx.parallel => (Some(path.clone()), None),
+        Some((path, f)) => (Some(path.clone()), Some(f.try_clone()?)),
+        None if cli.format != Format::Text => {
+            let (path, f) = open_log(ctx.is, i)?;
+            (Some(path), Some(f))
+        }
+        None => (None, None),
+    };
+    let capture = ctx.parallel && level != Level::Off && log_out.is_none();
+
+    // Log settings are per thread, so they are set up
+    // on the thread running the day.
+    let exec = move || {
+        aoc23::log::set_level(level);
+        if let Some(f) = log_out {
+            aoc23::log::set_output(Some(Box::new(BufWriter::new(f))));
+        }
+
+        let run = || match input {
+            Ok(input) => day.run(&input, part),
+            Err(err) => DayResult::failed(part, err),
+        };
+        let r = if capture {
+            aoc23::log::capture(run)
+        } else {
+            (run(), String::new())
+        };
+
+        aoc23::log::set_output(None);
+        r
+    };
+
+    let (dr, captured) = match cli.timeout {
+        None => exec(),
+        Some(limit) => {
+            // A day that times out can't be stopped,
+            // its thread is left running until the program exits.
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || tx.send(exec()));
+            match rx.recv_timeout(limit) {
+                Ok(r) => r,
+                Err(RecvTimeoutError::Timeout) => {
+                    (DayResult::timed_out(part, limit), String::new())
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    let err = anyhow!("day {} exited unexpectedly", i);
+                    (DayResult::failed(part, err), String::new())
+                }
+            }
+        }
+    };
+
+    let checks = match ctx.store {
+        Some(store) => check_answers(store, i, &dr, cli.record)?,
+        None => vec![],
+    };
+    let checks = if cli.check { checks } else { vec![] };
+
+    Ok(DayOutput {
+        i,
+        dr,
+        checks,
+        log,
+        captured,
+    })
+}
+
+fn print_day(i: usize, dr: &DayResult, checks: &[Option<Check>]) {
+    if let Some(err) = &dr.error {
+        let status = if dr.is_timeout() { "TIMEOUT" } else { "FAILED" };
+        println!("Day {:2}: {}", i, status);
+        eprintln!("{:#}", err);
+        return;
+    }
+
+    println!("Day {:2} parse:  ({})", i, fmt_duration(dr.parse_time));
+    for (n, pr) in dr.parts.iter().enumerate() {
+        print!("Day {:2} part {}: ", i, pr.part);
+        match &pr.answer {
+            Ok(answer) => {
+                print!("{}  ({})", answer, fmt_duration(pr.elapsed));
+                if let Some(Some(c)) = checks.get(n) {
+                    print!("  {}", c);
+                }
                 println!();
-                eprintln!("{}", e);
             }
+            Err(err) => {
+                println!("FAILED  ({})", fmt_duration(pr.elapsed));
+                eprintln!("{:#}", err);
+            }
+        }
+    }
+    println!("Day {:2} total:  ({})", i, fmt_duration(dr.elapsed()));
+}
+
+// WatchPart is the outcome of a part in watch mode, kept to show
+// the changes of the next run, even across rebuilds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WatchPart {
+    day: usize,
+    part: u8,
+    answer: Option<String>,
+    secs: f64,
+}
+
+// WATCH_STATE_VAR passes the last results of watch mode to the rebuilt runner.
+const WATCH_STATE_VAR: &str = "AOC23_WATCH_STATE";
+
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+// run_watch runs the days each time their inputs change, and rebuilds and
+// restarts the runner when its sources change.
+fn run_watch(cli: &Cli, is: &InputSource, days: &[(usize, &'static dyn Day)]) -> Result<()> {
+    // the path of the executable may not be found after it is rebuilt
+    let exe = std::env::current_exe().context("executable not found")?;
+
+    let inputs = match &cli.input {
+        Some(path) => vec![path.clone()],
+        None if cli.example.is_some() => vec![],
+        None => {
+            let dir = is
+                .cache_path()
+                .ok_or_else(|| anyhow!("cache dir not found"))?;
+            days.iter().map(|(i, _)| dir.join(i.to_string())).collect()
+        }
+    };
+    // sources are rescanned on each poll, so that new modules trigger a rebuild too
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut watcher = Watcher::new(inputs.iter().cloned().chain([root.join("Cargo.toml")]))
+        .with_dir(&root.join("src"), "rs");
+
+    let mut last: Vec<WatchPart> = std::env::var(WATCH_STATE_VAR)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    let filter = log_filter(cli);
+    loop {
+        print!("\x1b[2J\x1b[H");
+        let mut results = vec![];
+        for &(i, day) in days {
+            aoc23::log::set_level(filter.level(i));
+            let input = match &cli.input {
+                Some(path) => std::fs::read_to_string(path)
+                    .with_context(|| format!("error reading input file {:?}", path)),
+                None => get_input(cli, is, i),
+            };
+            match input {
+                Ok(input) => print_watch_day(i, &day.run(&input, cli.part), &last, &mut results),
+                Err(err) => {
+                    println!("Day {:2}: FAILED", i);
+                    eprintln!("{:#}", err);
+                }
+            }
+        }
+        last = results;
+
+        // inputs fetched by this run are not changes, sources edited meanwhile are
+        let mut changed = watcher.changed();
+        changed.retain(|p| !inputs.contains(p));
+        println!();
+        println!("Watching inputs and sources for changes, press Ctrl-C to stop");
+        loop {
+            if changed.is_empty() {
+                changed = watcher.wait(WATCH_INTERVAL);
+            }
+            if changed.iter().any(|p| !inputs.contains(p)) {
+                // returns only if the build failed
+                rebuild(&exe, &last)?;
+                changed.retain(|p| inputs.contains(p));
+            }
+            if !changed.is_empty() {
+                break;
+            }
+        }
+    }
+}
+
+// print_watch_day prints the results of day i with the changes since the last run,
+// and adds them to results.
+fn print_watch_day(i: usize, dr: &DayResult, last: &[WatchPart], results: &mut Vec<WatchPart>) {
+    if let Some(err) = &dr.error {
+        println!("Day {:2}: FAILED", i);
+        eprintln!("{:#}", err);
+        return;
+    }
+
+    for pr in &dr.parts {
+        let answer = pr.answer.as_ref().ok().cloned();
+        let secs = pr.elapsed.as_secs_f64();
+        let old = last.iter().find(|p| p.day == i && p.part == pr.part);
+
+        print!(
+            "Day {:2} part {}: {}",
+            i,
+            pr.part,
+            answer.as_deref().unwrap_or("FAILED")
+        );
+        if let Some(old) = old.filter(|old| old.answer != answer) {
+            print!("  (was {})", old.answer.as_deref().unwrap_or("FAILED"));
+        }
+        print!("  ({}", fmt_duration(pr.elapsed));
+        if let Some(old) = old.filter(|old| old.secs > 0.0) {
+            print!(", {:+.0}%", (secs - old.secs) / old.secs * 100.0);
         }
+        println!(")");
+        if let Err(err) = &pr.answer {
+            eprintln!("{:#}", err);
+        }
+
+        results.push(WatchPart {
+            day: i,
+            part: pr.part,
+            answer,
+            secs,
+        });
+    }
+}
+
+// rebuild builds the runner after its sources changed, and replaces the running
+// process with the new one, passing it the last results. It returns if the build
+// failed, so that watching can go on.
+fn rebuild(exe: &Path, last: &[WatchPart]) -> Result<()> {
+    print!("\x1b[2J\x1b[H");
+    println!("Sources changed, rebuilding");
+    let mut cmd = std::process::Command::new(option_env!("CARGO").unwrap_or("cargo"));
+    cmd.arg("build").current_dir(env!("CARGO_MANIFEST_DIR"));
+    if !cfg!(debug_assertions) {
+        cmd.arg("--release");
+    }
+    let status = cmd.status().context("error running cargo build")?;
+    if !status.success() {
+        println!("Build failed, watching sources for changes");
+        return Ok(());
+    }
+
+    let mut cmd = std::process::Command::new(exe);
+    cmd.args(std::env::args_os().skip(1))
+        .env(WATCH_STATE_VAR, serde_json::to_string(last)?);
+    exec(cmd)
+}
+
+#[cfg(unix)]
+fn exec(mut cmd: std::process::Command) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+    Err(cmd.exec()).context("error restarting after rebuild")
+}
+
+#[cfg(not(unix))]
+fn exec(mut cmd: std::process::Command) -> Result<()> {
+    let status = cmd.status().context("error restarting after rebuild")?;
+    std::process::exit(status.code().unwrap_or(1))
+}
+
+fn run_bench(
+    cli: &Cli,
+    is: &InputSource,
+    days: &[(usize, &'static dyn Day)],
+    input_override: Option<&str>,
+    runs: u32,
+) -> Result<()> {
+    let cache_path = is
+        .cache_path()
+        .ok_or_else(|| anyhow!("cache dir not found"))?;
+    let history = History::new(cache_path.join("bench").join("history.jsonl"));
+
+    // Look up the baseline before this run is added to the history.
+    let baseline = match &cli.compare {
+        Some(x) if Path::new(x).is_file() => Some(Report::load(Path::new(x))?),
+        Some(rev) => Some(
+            history
+                .find(rev)?
+                .ok_or_else(|| anyhow!("no benchmark of revision {} in history", rev))?,
+        ),
+        None => None,
+    };
+
+    let mut report = Report::new(git_rev(), runs);
+    let mut n_failed = 0;
+    let filter = log_filter(cli);
+    for &(i, day) in days {
+        aoc23::log::set_level(filter.level(i));
+        let db = match input_override {
+            Some(input) => bench::bench(day, i, input, cli.part, runs),
+            None => {
+                get_input(cli, is, i).and_then(|input| bench::bench(day, i, &input, cli.part, runs))
+            }
+        };
+        match db {
+            Ok(db) => {
+                print_bench(&db);
+                report.days.push(db);
+            }
+            Err(err) => {
+                println!("Day {:2}: FAILED", i);
+                eprintln!("{:#}", err);
+                n_failed += 1;
+            }
+        }
+    }
+
+    print_bench_totals(&report);
+
+    let path = match &cli.bench_out {
+        Some(path) => path.clone(),
+        None => cache_path.join("bench.json"),
+    };
+    report.save(&path)?;
+    history.append(&report)?;
+    println!("Results written to {:?}", path);
+
+    let n_regressed = match &baseline {
+        Some(baseline) => print_comparison(baseline, &report, cli.threshold),
+        None => 0,
+    };
+
+    if n_failed > 0 {
+        bail!("{} day(s) failed", n_failed);
+    }
+    if n_regressed > 0 {
+        bail!("{} day(s) regressed", n_regressed);
     }
 
     Ok(())
 }
 
+// run_submit submits the answer of part of day unless it is known to be wrong,
+// and records the attempt. The answer is always computed from the puzzle input.
+fn run_submit(cli: &Cli, is: &InputSource, i: usize, part: u8) -> Result<()> {
+    if cli.example.is_some() || cli.input.is_some() || cli.stdin {
+        bail!("submit answers the puzzle input, --example, --input and --stdin are not allowed");
+    }
+    let day = aoc23::day(cli.year, i)
+        .ok_or_else(|| anyhow!("no solution for day {} of {}", i, cli.year))?;
+    let input = is.get(i)?;
+    let mut dr = day.run(&input, Some(part));
+    if let Some(err) = dr.error {
+        return Err(err);
+    }
+    let answer = dr
+        .parts
+        .pop()
+        .ok_or_else(|| anyhow!("day {} has no part {}", i, part))?
+        .answer?;
+
+    let attempts = Attempts::new(
+        is.cache_path()
+            .ok_or_else(|| anyhow!("cache dir not found"))?
+            .join("submissions"),
+    );
+    if let Some(reason) = submit::known_verdict(&attempts.load(i)?, part, &answer) {
+        bail!("not submitting: {}", reason);
+    }
+
+    println!("Day {:2} part {}: submitting {}", i, part, answer);
+    let verdict = is.submit(i, part, &answer)?;
+    attempts.append(i, &Attempt::new(part, &answer, verdict.clone()))?;
+    if verdict != Verdict::Correct {
+        bail!("{}", verdict);
+    }
+    println!("{}", verdict);
+
+    let store = answer_store(is)?;
+    let mut answers = store.load(i)?;
+    answers.set(part, &answer);
+    store.save(i, &answers)
+}
+
+fn run_cache(is: &InputSource, cmd: &CacheCommand) -> Result<()> {
+    match cmd {
+        CacheCommand::List => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            for e in is.cached()? {
+                let fetched = match &e.meta {
+                    Some(meta) => format!("fetched {}", fmt_age(now.saturating_sub(meta.fetched))),
+                    None => "fetch time unknown".to_string(),
+                };
+                println!("Day {:2}  {:>8} bytes  {}", e.day, e.size, fetched);
+            }
+        }
+        CacheCommand::Show { day } => {
+            let input = is
+                .get_cache(*day)
+                .ok_or_else(|| anyhow!("input of day {} is not cached", day))?;
+            print!("{}", input);
+        }
+        CacheCommand::Verify => {
+            let mut n_bad = 0;
+            for e in is.cached()? {
+                let problems = e.verify();
+                if problems.is_empty() {
+                    println!("Day {:2}: OK", e.day);
+                } else {
+                    println!("Day {:2}: {}", e.day, problems.join(", "));
+                    n_bad += 1;
+                }
+            }
+            if n_bad > 0 {
+                bail!("{} cached input(s) look invalid", n_bad);
+            }
+        }
+        CacheCommand::Purge { day } => {
+            let n = is.purge(*day)?;
+            println!("Removed {} cached input(s)", n);
+        }
+    }
+    Ok(())
+}
+
+// print_comparison prints the changes of the day totals since baseline,
+// and returns the number of days regressed above threshold percent.
+fn print_comparison(baseline: &Report, report: &Report, threshold: f64) -> usize {
+    println!();
+    println!(
+        "Comparing with {} ({})",
+        baseline.rev.as_deref().unwrap_or("unknown revision"),
+        fmt_age(report.timestamp.saturating_sub(baseline.timestamp))
+    );
+    println!(
+        "{:>6}  {:>10}  {:>10}  {:>8}",
+        "Day", "before", "after", "change"
+    );
+    let mut n = 0;
+    for dc in bench::compare(baseline, report) {
+        let flag = if dc.is_regression(threshold) {
+            n += 1;
+            "  REGRESSION"
+        } else if dc.percent() < -threshold {
+            "  faster"
+        } else {
+            ""
+        };
+        println!(
+            "{:>6}  {:>10}  {:>10}  {:>+7.1}%{}",
+            dc.day,
+            fmt_duration(dc.old),
+            fmt_duration(dc.new),
+            dc.percent(),
+            flag
+        );
+    }
+    n
+}
+
+fn fmt_age(secs: u64) -> String {
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+// git_rev returns the git revision of the source tree, if it is known.
+fn git_rev() -> Option<String> {
+    let out = std::process::Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let rev = String::from_utf8(out.stdout).ok()?;
+    Some(rev.trim().to_string())
+}
+
+fn print_bench(db: &DayBench) {
+    println!("Day {:2} parse:  {}", db.day, fmt_stats(&db.parse));
+    for pb in &db.parts {
+        println!(
+            "Day {:2} part {}: {}",
+            db.day,
+            pb.part,
+            fmt_stats(&pb.stats)
+        );
+    }
+}
+
+fn print_bench_totals(report: &Report) {
+    println!();
+    println!(
+        "{:>6}  {:>10}  {:>10}  {:>10}  {:>10}",
+        "Day", "min", "median", "mean", "stddev"
+    );
+    let row = |label: &str, s: &Stats| {
+        println!(
+            "{:>6}  {:>10}  {:>10}  {:>10}  {:>10}",
+            label,
+            fmt_duration(s.min),
+            fmt_duration(s.median),
+            fmt_duration(s.mean),
+            fmt_duration(s.stddev)
+        );
+    };
+    for db in &report.days {
+        row(&db.day.to_string(), &db.total);
+    }
+    row("Total", &report.total());
+}
+
+fn fmt_stats(s: &Stats) -> String {
+    format!(
+        "min {}  median {}  mean {}  stddev {}",
+        fmt_duration(s.min),
+        fmt_duration(s.median),
+        fmt_duration(s.mean),
+        fmt_duration(s.stddev)
+    )
+}
+
+fn log_filter(cli: &Cli) -> Filter {
+    match &cli.log {
+        Some(f) => f.clone(),
+        None if cli.verbose => Filter::new(Level::Debug),
+        None => Filter::new(Level::Off),
+    }
+}
+
+// open_log creates the file for the verbose output of day i.
+fn open_log(is: &InputSource, i: usize) -> Result<(PathBuf, File)> {
+    let dir = is
+        .cache_path()
+        .ok_or_else(|| anyhow!("cache dir not found"))?
+        .join("logs");
+    std::fs::create_dir_all(&dir).with_context(|| format!("error creating log dir {:?}", dir))?;
+    let path = dir.join(format!("day{:02}.log", i));
+    let f = File::create(&path).with_context(|| format!("error creating log file {:?}", path))?;
+    Ok((path, f))
+}
+
+fn load_profile(cli: &Cli) -> Result<Profile> {
+    let config = match cli.config.clone().or_else(Config::default_path) {
+        Some(path) => Config::load(&path)?,
+        None => Config::parse("")?,
+    };
+    let name = cli.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    match config.profile(name) {
+        Some(p) => Ok(p.clone()),
+        None => {
+            let names: Vec<_> = config.profile_names().collect();
+            bail!("unknown profile {}, available: {}", name, names.join(", "))
+        }
+    }
+}
+
+fn answer_store(is: &InputSource) -> Result<AnswerStore> {
+    let path = is
+        .cache_path()
+        .ok_or_else(|| anyhow!("cache dir not found"))?;
+    Ok(AnswerStore::new(path.join("answers")))
+}
+
+// check_answers checks the answers of the parts in dr against the recorded ones,
+// and records them as the expected ones if `record` is set.
+fn check_answers(
+    store: &AnswerStore,
+    i: usize,
+    dr: &DayResult,
+    record: bool,
+) -> Result<Vec<Option<Check>>> {
+    let mut answers = store.load(i)?;
+
+    let checks = dr
+        .parts
+        .iter()
+        .map(|pr| {
+            let answer = pr.answer.as_ref().ok()?;
+            Some(answers.check(pr.part, answer))
+        })
+        .collect::<Vec<_>>();
+
+    if record && dr.error.is_none() {
+        for pr in &dr.parts {
+            if let Ok(answer) = &pr.answer {
+                answers.set(pr.part, answer);
+            }
+        }
+        store.save(i, &answers)?;
+    }
+
+    Ok(checks)
+}
+
 fn fmt_duration(d: Duration) -> String {
     let ms = d.as_secs_f64() * 1000.0;
+    if ms < 1.0 {
+        return format!("{:.1}µs", ms * 1000.0);
+    }
     if ms < 100.0 {
         return format!("{:.1}ms", ms);
     }
@@ -103,24 +974,55 @@ fn fmt_duration(d: Duration) -> String {
     fmt
 }
 
-pub fn verbose() -> bool {
-    CLI_INSTANCE.get().map(|cli| cli.verbose).unwrap_or(true)
+fn parse_year(s: &str) -> Result<u32, String> {
+    aoc23::parse_year(s).map_err(|err| format!("{err}"))
 }
 
-type DayFunc = fn(&str) -> Result<String>;
+fn parse_secs(s: &str) -> Result<Duration, String> {
+    let secs: f64 = s.parse().map_err(|err| format!("{err}"))?;
+    Duration::try_from_secs_f64(secs).map_err(|err| format!("{err}"))
+}
 
-fn get_day_funcs(cli: &Cli) -> Vec<(usize, DayFunc)> {
-    let v: Vec<(usize, DayFunc)> = DAY_FNS
-        .iter()
-        .enumerate()
-        .map(|(n, &f)| (n + 1, f))
-        .collect();
-    if !cli.days.is_empty() {
+// get_input returns the input or the example selected by cli of day i.
+fn get_input(cli: &Cli, is: &InputSource, i: usize) -> Result<String> {
+    match cli.example {
+        Some(n) => is.example(i, n as usize),
+        None => is.get(i),
+    }
+}
+
+fn read_input_override(cli: &Cli) -> Result<Option<String>> {
+    if let Some(path) = &cli.input {
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("error reading input file {:?}", path))?;
+        Ok(Some(s))
+    } else if cli.stdin {
+        let mut s = String::new();
+        std::io::stdin()
+            .read_to_string(&mut s)
+            .context("error reading input from stdin")?;
+        Ok(Some(s))
+    } else {
+        Ok(None)
+    }
+}
+
+fn get_days(cli: &Cli) -> Result<Vec<(usize, &'static dyn Day)>> {
+    let year = aoc23::year(cli.year).ok_or_else(|| {
+        let years: Vec<_> = aoc23::years().map(|y| y.year.to_string()).collect();
+        anyhow!(
+            "no solutions for year {}, available: {}",
+            cli.year,
+            years.join(", ")
+        )
+    })?;
+    let v: Vec<(usize, &'static dyn Day)> = year.days().collect();
+    Ok(if !cli.days.is_empty() {
         let s: HashSet<_> = cli.days.iter().collect();
         v.into_iter().filter(|(x, _)| s.contains(&x)).collect()
     } else if cli.all {
         v
     } else {
-        vec![*v.last().unwrap()]
-    }
+        v.last().copied().into_iter().collect()
+    })
 }
@@ -0,0 +1,250 @@
+//! Machine-readable output of day results.
+//!
+//! Every part, and every day that failed before its parts could run,
+//! produces a [`Record`] that is written in one of the [`Format`]s.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::answers::Check;
+use crate::DayResult;
+
+/// Output format of the runner.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line
+    Json,
+    /// Comma-separated values with a header line
+    Csv,
+    /// Test Anything Protocol
+    Tap,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            "csv" => Format::Csv,
+            "tap" => Format::Tap,
+            _ => bail!("unknown format {s}, use text, json, csv or tap"),
+        })
+    }
+}
+
+/// Result of a part, or the error of a day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Record {
+    pub day: usize,
+
+    /// Part of the result, None for errors of the whole day.
+    pub part: Option<u8>,
+
+    pub answer: Option<String>,
+
+    /// Run time in seconds.
+    #[serde(serialize_with = "secs")]
+    pub duration: Option<Duration>,
+
+    pub error: Option<String>,
+
+    /// Result of checking the answer, if requested.
+    pub check: Option<String>,
+
+    /// File containing the log output of the day, if any.
+    pub log: Option<PathBuf>,
+}
+
+impl Record {
+    /// Records of the parts in `dr`, or a single record if the day failed.
+    pub fn from_day(
+        day: usize,
+        dr: &DayResult,
+        checks: &[Option<Check>],
+        log: Option<PathBuf>,
+    ) -> Vec<Record> {
+        if let Some(err) = &dr.error {
+            return vec![Record {
+                day,
+                part: None,
+                answer: None,
+                duration: None,
+                error: Some(format!("{:#}", err)),
+                check: None,
+                log,
+            }];
+        }
+
+        dr.parts
+            .iter()
+            .enumerate()
+            .map(|(n, pr)| Record {
+                day,
+                part: Some(pr.part),
+                answer: pr.answer.as_ref().ok().cloned(),
+                duration: Some(pr.elapsed),
+                error: pr.answer.as_ref().err().map(|err| format!("{:#}", err)),
+                check: checks.get(n).cloned().flatten().map(|c| c.to_string()),
+                log: log.clone(),
+            })
+            .collect()
+    }
+
+    /// Reports if the part succeeded and its check did not fail.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none() && !self.check.as_deref().is_some_and(|c| c.starts_with("FAIL"))
+    }
+}
+
+/// Writes records in a machine-readable format.
+pub struct RecordWriter<W: Write> {
+    w: W,
+    format: Format,
+    n: usize,
+}
+
+impl<W: Write> RecordWriter<W> {
+    /// Creates a writer and writes the header of `format`.
+    pub fn new(mut w: W, format: Format) -> Result<Self> {
+        match format {
+            Format::Csv => writeln!(w, "day,part,answer,duration,error,check,log")?,
+            Format::Tap => writeln!(w, "TAP version 13")?,
+            _ => {}
+        }
+        Ok(Self { w, format, n: 0 })
+    }
+
+    pub fn write(&mut self, r: &Record) -> Result<()> {
+        self.n += 1;
+        let w = &mut self.w;
+        match self.format {
+            Format::Text => bail!("text format is not supported by RecordWriter"),
+            Format::Json => writeln!(w, "{}", serde_json::to_string(r)?)?,
+            Format::Csv => {
+                let fields = [
+                    r.day.to_string(),
+                    r.part.map(|p| p.to_string()).unwrap_or_default(),
+                    r.answer.clone().unwrap_or_default(),
+                    r.duration
+                        .map(|d| d.as_secs_f64().to_string())
+                        .unwrap_or_default(),
+                    r.error.clone().unwrap_or_default(),
+                    r.check.clone().unwrap_or_default(),
+                    r.log
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default(),
+                ];
+                let fields = fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>();
+                writeln!(w, "{}", fields.join(","))?;
+            }
+            Format::Tap => {
+                let status = if r.is_ok() { "ok" } else { "not ok" };
+                let what = match r.part {
+                    Some(p) => format!("day {} part {}", r.day, p),
+                    None => format!("day {}", r.day),
+                };
+                match &r.answer {
+                    Some(answer) => writeln!(w, "{} {} - {}: {}", status, self.n, what, answer)?,
+                    None => writeln!(w, "{} {} - {}", status, self.n, what)?,
+                }
+                writeln!(w, "  ---")?;
+                if let Some(d) = r.duration {
+                    writeln!(w, "  duration_ms: {}", d.as_secs_f64() * 1000.0)?;
+                }
+                if let Some(err) = &r.error {
+                    writeln!(w, "  message: {}", yaml_str(err))?;
+                }
+                if let Some(check) = &r.check {
+                    writeln!(w, "  check: {}", yaml_str(check))?;
+                }
+                if let Some(log) = &r.log {
+                    writeln!(w, "  log: {}", yaml_str(&log.display().to_string()))?;
+                }
+                writeln!(w, "  ...")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the trailer of the format and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        if self.format == Format::Tap {
+            writeln!(self.w, "1..{}", self.n)?;
+        }
+        self.w.flush()?;
+        Ok(self.w)
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// yaml_str quotes s as a YAML string for TAP diagnostics.
+fn yaml_str(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+fn secs<S: serde::Serializer>(d: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+    match d {
+        Some(d) => s.serialize_some(&d.as_secs_f64()),
+        None => s.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let rec = |answer: Option<&str>, error: Option<&str>| Record {
+            day: 3,
+            part: Some(1),
+            answer: answer.map(String::from),
+            duration: Some(Duration::from_millis(2)),
+            error: error.map(String::from),
+            check: None,
+            log: None,
+        };
+        let write = |format| {
+            let mut w = RecordWriter::new(vec![], format).unwrap();
+            w.write(&rec(Some("42"), None)).unwrap();
+            w.write(&rec(None, Some("bad \"input\", line 1"))).unwrap();
+            String::from_utf8(w.finish().unwrap()).unwrap()
+        };
+
+        assert_eq!(
+            write(Format::Csv),
+            "day,part,answer,duration,error,check,log
+3,1,42,0.002,,,
+3,1,,0.002,\"bad \"\"input\"\", line 1\",,
+"
+        );
+
+        let json = write(Format::Json);
+        assert!(json.starts_with(r#"{"day":3,"part":1,"answer":"42","duration":0.002,"#));
+        assert_eq!(json.lines().count(), 2);
+
+        let tap = write(Format::Tap);
+        assert!(tap.contains("\nok 1 - day 3 part 1: 42\n"));
+        assert!(tap.contains("\nnot ok 2 - day 3 part 1\n"));
+        assert!(tap.ends_with("1..2\n"));
+
+        assert_eq!("tap".parse::<Format>().ok(), Some(Format::Tap));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
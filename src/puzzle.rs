@@ -0,0 +1,104 @@
+//! Examples extracted from the puzzle descriptions.
+//!
+//! The puzzle pages show the example inputs in `<pre><code>` blocks,
+//! they are numbered from 1 in the order of the page.
+
+/// Extracts the text of the `<pre><code>` blocks of the puzzle page `html`.
+pub fn extract_examples(html: &str) -> Vec<String> {
+    const START: &str = "<pre><code>";
+    const END: &str = "</code></pre>";
+
+    let mut v = vec![];
+    let mut rest = html;
+    while let Some(n) = rest.find(START) {
+        rest = &rest[n + START.len()..];
+        let Some(end) = rest.find(END) else {
+            break;
+        };
+        let mut s = decode_entities(&strip_tags(&rest[..end]));
+        if !s.ends_with('\n') {
+            s.push('\n');
+        }
+        v.push(s);
+        rest = &rest[end + END.len()..];
+    }
+    v
+}
+
+/// Reports if the puzzle page `html` shows the description of part two,
+/// which is available only after part one is solved.
+pub fn has_part_two(html: &str) -> bool {
+    html.contains("--- Part Two ---")
+}
+
+// strip_tags removes the markup, eg. the `<em>` highlights, from s.
+pub(crate) fn strip_tags(s: &str) -> String {
+    let mut r = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => r.push(c),
+            _ => {}
+        }
+    }
+    r
+}
+
+// decode_entities replaces the named and numeric character references in s.
+fn decode_entities(s: &str) -> String {
+    let mut r = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(n) = rest.find('&') {
+        r.push_str(&rest[..n]);
+        rest = &rest[n..];
+        let decoded = rest.find(';').and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                e => {
+                    let code = match e.strip_prefix("#x").or_else(|| e.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => e.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                r.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                r.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    r.push_str(rest);
+    r
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let html = "<p>For example:</p>
+<pre><code>#.<em>#</em>\n&lt;&gt; &amp;&#39; &#x41;&nope; a&amp\n</code></pre>
+<p>Here <code>x</code> is not an example.</p>
+<pre><code>1 2</code></pre>";
+        assert_eq!(
+            extract_examples(html),
+            vec!["#.#\n<> &' A&nope; a&amp\n", "1 2\n"]
+        );
+        assert!(!has_part_two(html));
+    }
+}
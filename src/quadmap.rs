@@ -1,9 +1,11 @@
+//! Sparse, unbounded two dimensional map.
+
 use std::cmp::{max, min};
 use std::collections::HashMap;
 
 const SIZE: i32 = 16; // x/y size of one block
 
-#[allow(unused)]
+/// Sparse map storing values in blocks, with `zero` in unset cells.
 #[derive(Debug)]
 pub struct Map<T> {
     zero: T,
@@ -11,7 +13,6 @@ pub struct Map<T> {
     bounds: Bounds,
 }
 
-#[allow(unused)]
 impl<T: Copy> Map<T> {
     pub fn new(zero: T) -> Map<T> {
         Map {
@@ -21,6 +22,7 @@ impl<T: Copy> Map<T> {
         }
     }
 
+    /// Value at `p`, or zero if unset.
     pub fn at(&self, p: (i32, i32)) -> &T {
         let (k, o) = self.mpos(p);
         if let Some(v) = self.m.get(&k) {
@@ -30,6 +32,7 @@ impl<T: Copy> Map<T> {
         }
     }
 
+    /// Mutable value at `p`, extending bounds as needed.
     pub fn at_mut(&mut self, p: (i32, i32)) -> &mut T {
         self.bounds.extend_one(p);
         let (k, o) = self.mpos(p);
@@ -40,6 +43,7 @@ impl<T: Copy> Map<T> {
         &mut v[o]
     }
 
+    /// Bounds of all cells accessed with `at_mut`.
     pub fn bounds(&self) -> &Bounds {
         &self.bounds
     }
@@ -66,12 +70,19 @@ impl<T: Copy> Map<T> {
     }
 }
 
+/// Rectangle between `min` (inclusive) and `max` (exclusive).
 #[derive(Debug, Copy, Clone)]
 pub struct Bounds {
     pub min: (i32, i32),
     pub max: (i32, i32),
 }
 
+impl Default for Bounds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Bounds {
     pub fn new() -> Bounds {
         Self {
@@ -0,0 +1,253 @@
+//! Solutions of the days and their results.
+
+use anyhow::{anyhow, Result};
+use std::cell::Cell;
+use std::fmt::{self, Display};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+/// Solution of a single day.
+///
+/// The input is parsed once using `parse`,
+/// and the result is passed to both parts.
+/// The lifetime 'a is that of the input text borrowed by Input, if any.
+pub trait Solution<'a> {
+    type Input;
+
+    fn parse(&self, input: &'a str) -> Result<Self::Input>;
+
+    fn part1(&self, input: &Self::Input) -> Result<impl Display>;
+
+    fn part2(&self, input: &Self::Input) -> Result<impl Display>;
+}
+
+/// Day is the object safe interface of Solution used by the day registry.
+pub trait Day: Sync {
+    /// Run parts of the day selected by `part`, or both parts if `part` is None.
+    fn run(&self, input: &str, part: Option<u8>) -> DayResult;
+}
+
+impl<S> Day for S
+where
+    S: for<'a> Solution<'a> + Sync,
+{
+    fn run(&self, input: &str, part: Option<u8>) -> DayResult {
+        let mut dr = DayResult::new(part);
+
+        let now = Instant::now();
+        let parsed = catch_panic(|| self.parse(input));
+        dr.parse_time = now.elapsed();
+
+        match parsed {
+            Ok(x) => {
+                dr.part1(|| self.part1(&x));
+                dr.part2(|| self.part2(&x));
+            }
+            Err(err) => dr.error = Some(err),
+        }
+
+        dr
+    }
+}
+
+/// Result of running one day, with answers and timings for each part.
+#[derive(Debug)]
+pub struct DayResult {
+    // selected part, or None if both parts should run
+    part: Option<u8>,
+
+    /// Error that prevented the parts from running, eg. invalid input.
+    pub error: Option<anyhow::Error>,
+
+    /// Time spent parsing the input.
+    pub parse_time: Duration,
+
+    /// Results of the parts that were run.
+    pub parts: Vec<PartResult>,
+}
+
+/// Answer of one part and the time it took to compute.
+#[derive(Debug)]
+pub struct PartResult {
+    pub part: u8,
+    pub answer: Result<String>,
+    pub elapsed: Duration,
+}
+
+impl DayResult {
+    /// Creates an empty result running `part`, or both parts if None.
+    pub fn new(part: Option<u8>) -> Self {
+        Self {
+            part,
+            error: None,
+            parse_time: Duration::ZERO,
+            parts: vec![],
+        }
+    }
+
+    /// Creates a result for a day that could not be run at all.
+    pub fn failed(part: Option<u8>, err: anyhow::Error) -> Self {
+        let mut dr = Self::new(part);
+        dr.error = Some(err);
+        dr
+    }
+
+    /// Creates a result for a day that did not finish within `limit`.
+    pub fn timed_out(part: Option<u8>, limit: Duration) -> Self {
+        Self::failed(part, anyhow!(Timeout(limit)))
+    }
+
+    /// Reports if the day did not finish in time.
+    pub fn is_timeout(&self) -> bool {
+        self.error.as_ref().is_some_and(|err| err.is::<Timeout>())
+    }
+
+    /// Reports if `part` is selected to run.
+    pub fn wants(&self, part: u8) -> bool {
+        self.part.map(|p| p == part).unwrap_or(true)
+    }
+
+    /// Runs part 1 using `f` if selected, and records its answer and time.
+    /// Parts returning [`NoPuzzle`] are left out of the results.
+    pub fn part1<T: Display>(&mut self, f: impl FnOnce() -> Result<T>) {
+        self.run_part(1, f)
+    }
+
+    /// Runs part 2 using `f` if selected, and records its answer and time.
+    /// Parts returning [`NoPuzzle`] are left out of the results.
+    pub fn part2<T: Display>(&mut self, f: impl FnOnce() -> Result<T>) {
+        self.run_part(2, f)
+    }
+
+    /// Total time spent parsing and running the parts.
+    pub fn elapsed(&self) -> Duration {
+        self.parse_time + self.parts.iter().map(|p| p.elapsed).sum::<Duration>()
+    }
+
+    /// Reports if the day and all its parts succeeded.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none() && self.parts.iter().all(|p| p.answer.is_ok())
+    }
+
+    fn run_part<T: Display>(&mut self, part: u8, f: impl FnOnce() -> Result<T>) {
+        if !self.wants(part) {
+            return;
+        }
+
+        let now = Instant::now();
+        let answer = catch_panic(f).map(|x| x.to_string());
+        let elapsed = now.elapsed();
+        if answer.as_ref().is_err_and(|err| err.is::<NoPuzzle>()) {
+            return;
+        }
+        self.parts.push(PartResult {
+            part,
+            answer,
+            elapsed,
+        });
+    }
+}
+
+/// Error of a day that did not finish within the time limit.
+#[derive(Debug)]
+pub struct Timeout(pub Duration);
+
+impl Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out after {:?}", self.0)
+    }
+}
+
+impl std::error::Error for Timeout {}
+
+/// Error of a part that has no puzzle, eg. part two of day 25.
+#[derive(Debug)]
+pub struct NoPuzzle;
+
+impl Display for NoPuzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no puzzle")
+    }
+}
+
+impl std::error::Error for NoPuzzle {}
+
+thread_local! {
+    // set while the thread runs a part under catch_panic
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+// catch_panic runs f, and turns a panic into an error with the panic message.
+// The panic hook is silenced while f runs, as the message ends up in the error.
+fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    // parts run on several threads at once, so the hook is installed once and
+    // passes panics outside catch_panic to the previous hook
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let prev = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.get() {
+                prev(info);
+            }
+        }));
+    });
+
+    let catching = CATCHING.replace(true);
+    let r = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(catching);
+    match r {
+        Ok(r) => r,
+        Err(payload) => {
+            let msg = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown panic");
+            Err(anyhow!("panicked: {}", msg))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Panicky;
+
+    impl Solution<'_> for Panicky {
+        type Input = ();
+
+        fn parse(&self, _input: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn part1(&self, _input: &()) -> Result<impl Display> {
+            Ok(1)
+        }
+
+        #[allow(unreachable_code)]
+        fn part2(&self, _input: &()) -> Result<impl Display> {
+            panic!("invalid cycle") as Result<usize>
+        }
+    }
+
+    #[test]
+    fn it_works() {
+        let dr = Panicky.run("", None);
+        assert!(dr.error.is_none());
+        assert_eq!(dr.parts[0].answer.as_deref().ok(), Some("1"));
+        let err = dr.parts[1].answer.as_ref().unwrap_err();
+        assert_eq!(err.to_string(), "panicked: invalid cycle");
+
+        let mut dr = DayResult::new(None);
+        dr.part1(|| Ok(1));
+        dr.part2(|| Err(anyhow!(NoPuzzle)) as Result<u8>);
+        assert_eq!(dr.parts.len(), 1);
+        assert!(dr.is_ok());
+
+        let dr = DayResult::timed_out(None, Duration::from_secs(1));
+        assert!(dr.is_timeout());
+        assert!(!dr.is_ok());
+    }
+}
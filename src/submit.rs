@@ -0,0 +1,240 @@
+//! Answer submission and the record of earlier attempts.
+//!
+//! Every submitted answer is recorded with its [`Verdict`] in a JSON lines file
+//! per day, so that answers known to be wrong are never submitted again.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::puzzle::strip_tags;
+
+/// Response of the website to a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Answer given too recently, seconds left to wait.
+    Wait(u64),
+    /// The part is already solved, or part one is not solved yet.
+    WrongLevel,
+    /// Text of a response that was not understood.
+    Unknown(String),
+}
+
+impl Verdict {
+    /// Reports if the answer was judged, as opposed to the submission being rejected.
+    pub fn is_judged(&self) -> bool {
+        matches!(
+            self,
+            Verdict::Correct | Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow
+        )
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "right answer"),
+            Verdict::Wrong => write!(f, "wrong answer"),
+            Verdict::TooHigh => write!(f, "wrong answer, too high"),
+            Verdict::TooLow => write!(f, "wrong answer, too low"),
+            Verdict::Wait(secs) => write!(f, "answer given too recently, wait {}s", secs),
+            Verdict::WrongLevel => write!(f, "part already solved or not unlocked"),
+            Verdict::Unknown(text) => write!(f, "unknown response: {}", text),
+        }
+    }
+}
+
+/// Parses the page returned after submitting an answer.
+pub fn parse_response(html: &str) -> Verdict {
+    let text = match (html.find("<article>"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    };
+    let text = strip_tags(text);
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if text.contains("That's the right answer") {
+        Verdict::Correct
+    } else if text.contains("That's not the right answer") {
+        if text.contains("too high") {
+            Verdict::TooHigh
+        } else if text.contains("too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if text.contains("You gave an answer too recently") {
+        Verdict::Wait(parse_wait(&text).unwrap_or(60))
+    } else if text.contains("You don't seem to be solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        Verdict::Unknown(text)
+    }
+}
+
+// parse_wait finds the time left to wait in text, eg. "You have 1m 5s left to wait".
+fn parse_wait(text: &str) -> Option<u64> {
+    let start = text.find("You have ")? + "You have ".len();
+    let end = start + text[start..].find(" left to wait")?;
+    let mut secs = 0;
+    for w in text[start..end].split_whitespace() {
+        let (n, unit) = w.split_at(w.find(|c: char| !c.is_ascii_digit())?);
+        let n: u64 = n.parse().ok()?;
+        secs += match unit {
+            "h" => n * 3600,
+            "m" => n * 60,
+            "s" => n,
+            _ => return None,
+        };
+    }
+    Some(secs)
+}
+
+/// Answer submitted earlier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempt {
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+
+    /// Time of the submission in seconds since the unix epoch.
+    pub time: u64,
+}
+
+impl Attempt {
+    /// Creates an attempt submitted now.
+    pub fn new(part: u8, answer: &str, verdict: Verdict) -> Self {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            part,
+            answer: answer.to_string(),
+            verdict,
+            time,
+        }
+    }
+}
+
+/// Folder containing the attempts of all days.
+pub struct Attempts {
+    dir: PathBuf,
+}
+
+impl Attempts {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Loads the attempts of `day`, oldest first.
+    pub fn load(&self, day: usize) -> Result<Vec<Attempt>> {
+        let path = self.day_path(day);
+        let s = match fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => {
+                return Err(err).with_context(|| format!("error reading attempts {:?}", path))
+            }
+        };
+        s.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(ln, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("in attempts {:?} line {}", path, ln + 1))
+            })
+            .collect()
+    }
+
+    /// Records `attempt` of `day`.
+    pub fn append(&self, day: usize, attempt: &Attempt) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("error creating attempts dir {:?}", self.dir))?;
+        let path = self.day_path(day);
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("error opening attempts {:?}", path))?;
+        writeln!(f, "{}", serde_json::to_string(attempt)?)
+            .with_context(|| format!("error writing attempts {:?}", path))
+    }
+
+    fn day_path(&self, day: usize) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", day))
+    }
+}
+
+/// Explains why `answer` of `part` should not be submitted given the earlier
+/// `attempts`, or returns None if it may be right.
+///
+/// Numeric answers are also compared with the ones known to be too high or too low.
+pub fn known_verdict(attempts: &[Attempt], part: u8, answer: &str) -> Option<String> {
+    let attempts = || attempts.iter().filter(|a| a.part == part);
+    if let Some(a) = attempts().find(|a| a.verdict == Verdict::Correct) {
+        return Some(format!("part {} is already solved with {}", part, a.answer));
+    }
+    if let Some(a) = attempts().find(|a| a.answer == answer && a.verdict.is_judged()) {
+        return Some(format!("{} was submitted before: {}", answer, a.verdict));
+    }
+
+    let n: i128 = answer.parse().ok()?;
+    attempts().find_map(|a| {
+        let x: i128 = a.answer.parse().ok()?;
+        match a.verdict {
+            Verdict::TooLow if n <= x => Some(format!("{} is too low, {} was too low", n, x)),
+            Verdict::TooHigh if n >= x => Some(format!("{} is too high, {} was too high", n, x)),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let verdict = |s: &str| {
+            let html = format!("<html><main>\n<article><p>{s}</p></article>\n</main></html>");
+            parse_response(&html)
+        };
+        let v = verdict("That's the right answer!  You are <span>one gold star</span> closer.");
+        assert_eq!(v, Verdict::Correct);
+        let v = verdict("That's not the right answer; your answer is too low.  Please wait.");
+        assert_eq!(v, Verdict::TooLow);
+        let v = verdict("You gave an answer too recently; ...  You have 1m 5s left to wait.");
+        assert_eq!(v, Verdict::Wait(65));
+        let v =
+            verdict("You don't seem to be solving the right level.  Did you already complete it?");
+        assert_eq!(v, Verdict::WrongLevel);
+        assert!(matches!(parse_response("huh"), Verdict::Unknown(_)));
+
+        let attempts = [
+            Attempt::new(2, "52136", Verdict::TooLow),
+            Attempt::new(2, "60000", Verdict::Wait(30)),
+            Attempt::new(2, "90000", Verdict::TooHigh),
+        ];
+        assert!(known_verdict(&attempts, 2, "52136").is_some());
+        assert!(known_verdict(&attempts, 2, "52000").is_some());
+        assert!(known_verdict(&attempts, 2, "95000").is_some());
+        assert!(known_verdict(&attempts, 2, "60000").is_none());
+        assert!(known_verdict(&attempts, 1, "52136").is_none());
+
+        let solved = [Attempt::new(1, "142", Verdict::Correct)];
+        assert!(known_verdict(&solved, 1, "143").is_some());
+
+        let json = serde_json::to_string(&attempts[1]).unwrap();
+        assert!(json.contains(r#""verdict":{"wait":30}"#), "{json}");
+        assert_eq!(serde_json::from_str::<Attempt>(&json).unwrap(), attempts[1]);
+    }
+}
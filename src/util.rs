@@ -1,92 +1,730 @@
-use crate::AOC_YEAR;
-use anyhow::{Context, Result};
+use crate::config::{read_session_file, Profile, DEFAULT_PROFILE};
+use crate::puzzle::{extract_examples, has_part_two};
+use crate::submit::{parse_response, Verdict};
+use crate::DEFAULT_YEAR;
+use anyhow::{anyhow, bail, Context, Result};
 use directories::BaseDirs;
-use reqwest::blocking::Client;
+use once_cell::sync::OnceCell;
+use reqwest::blocking::{Client, RequestBuilder};
 use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs;
+use std::io::ErrorKind;
 use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+/// Source of puzzle inputs, fetched from the advent of code website
+/// using the session cookie and then cached locally.
+///
+/// The session is needed only when an input is not yet cached.
+/// The cache is in a folder of the year in the user's cache folder, unless set by the
+/// `AOC23_CACHE` environment variable or [`with_cache_dir`](Self::with_cache_dir).
+/// Inputs are fetched from [`DEFAULT_BASE_URL`], unless set by the
+/// `AOC23_BASE_URL` environment variable or [`with_base_url`](Self::with_base_url),
+/// eg. to use a mirror.
+///
+/// The environment variables are looked up for the year of the source,
+/// eg. `AOC22_SESSION` for 2022, falling back to the variable without the year
+/// like `AOC_SESSION`. The settings can also come from a profile of the
+/// [configuration file](crate::config), see [`with_profile`](Self::with_profile).
+///
+/// Requests identify the program and the contact set by `AOC23_CONTACT`
+/// or [`with_contact`](Self::with_contact) in the User-Agent header.
+/// They are spaced by a minimum interval, transient errors are retried
+/// with exponential backoff, and days that have not been released are
+/// never requested from the advent of code website.
 pub struct InputSource {
-    client: Client,
+    year: u32,
+    profile: String,
+    client: OnceCell<Client>,
+    session: Option<Session>,
+    base_url: String,
+    cache_dir: Option<PathBuf>,
+    offline: bool,
+    contact: Option<String>,
+    min_interval: Duration,
+    retries: u32,
+    backoff: Duration,
+    last_request: Mutex<Option<Instant>>,
+}
+
+// Session cookie, or the file it is read from once a request is made.
+enum Session {
+    Value(String),
+    File(PathBuf),
+}
+
+/// Address of the advent of code website.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Default minimum time between two requests.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default number of retries after a transient error.
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Default delay before the first retry, doubled for each further retry.
+pub const DEFAULT_BACKOFF: Duration = Duration::from_secs(2);
+
+impl Default for InputSource {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl InputSource {
-    pub fn new() -> Result<InputSource> {
-        let sessionvar = format!("AOC{}_SESSION", AOC_YEAR);
+    /// Creates an input source of [`DEFAULT_YEAR`] using the session,
+    /// url and cache from the environment.
+    pub fn new() -> InputSource {
+        Self::for_year(DEFAULT_YEAR)
+    }
+
+    /// Creates an input source of `year` using the session,
+    /// url and cache from the environment.
+    pub fn for_year(year: u32) -> InputSource {
+        let cache_dir = match env_var_os(year, "CACHE") {
+            Some(dir) => Some(PathBuf::from(dir)),
+            None => Self::default_cache_dir(year),
+        };
+        let base_url = env_var(year, "BASE_URL").unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        let contact = env_var(year, "CONTACT");
+        InputSource {
+            year,
+            profile: DEFAULT_PROFILE.to_string(),
+            client: OnceCell::new(),
+            session: None,
+            base_url,
+            cache_dir,
+            offline: false,
+            contact,
+            min_interval: DEFAULT_MIN_INTERVAL,
+            retries: DEFAULT_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+            last_request: Mutex::new(None),
+        }
+    }
+
+    /// Year of the puzzle inputs.
+    pub fn year(&self) -> u32 {
+        self.year
+    }
+
+    /// Uses the settings of `profile` from the configuration file.
+    ///
+    /// Settings of a named profile override the environment, and its inputs
+    /// are cached separately, as different accounts get different inputs.
+    /// The default profile is used only for settings missing from the environment.
+    /// A session file is read only when an input is fetched.
+    pub fn with_profile(mut self, profile: &Profile) -> Self {
+        let year = self.year;
+        let named = !profile.is_default();
+        let use_value = |name| named || env_var(year, name).is_none();
+
+        if use_value("SESSION") {
+            if let Some(session) = &profile.session {
+                self.session = Some(Session::Value(session.clone()));
+            } else if let Some(path) = &profile.session_file {
+                self.session = Some(Session::File(path.clone()));
+            }
+        }
+        if let (Some(contact), true) = (&profile.contact, use_value("CONTACT")) {
+            self.contact = Some(contact.clone());
+        }
+        if let (Some(url), true) = (&profile.base_url, use_value("BASE_URL")) {
+            self.base_url = url.clone();
+        }
+        if named {
+            self.cache_dir = self.cache_dir.map(|dir| {
+                let mut name = dir.file_name().unwrap_or_default().to_os_string();
+                name.push(format!("-{}", profile.name));
+                dir.with_file_name(name)
+            });
+        }
+        self.profile = profile.name.clone();
+        self
+    }
+
+    /// Name of the profile of the settings.
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Uses `session` as the session cookie instead of the environment.
+    pub fn with_session(mut self, session: impl Into<String>) -> Self {
+        self.session = Some(Session::Value(session.into()));
+        self
+    }
+
+    /// Fetches inputs from the site at `url`.
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into();
+        self
+    }
+
+    /// Base url of the site the inputs are fetched from.
+    pub fn base_url(&self) -> &str {
+        self.base_url.trim_end_matches('/')
+    }
+
+    /// Uses `dir` to cache inputs.
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Disables fetching, so that only cached inputs are available.
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
 
-        let session = env::var(&sessionvar).with_context(|| {
-            format!(
-                "Environment variable {sessionvar} is unset.
-Set it to the value of the `session` cookie from the advent of code website."
-            )
-        })?;
+    /// Includes `contact`, eg. an email address or repository url,
+    /// in the User-Agent header.
+    pub fn with_contact(mut self, contact: impl Into<String>) -> Self {
+        self.contact = Some(contact.into());
+        self
+    }
+
+    /// Waits at least `interval` between two requests.
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// Retries transient errors `retries` times, waiting `backoff`
+    /// before the first retry and twice as long before each further one.
+    pub fn with_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// User-Agent header sent with the requests.
+    pub fn user_agent(&self) -> String {
+        let name = env!("CARGO_PKG_NAME");
+        let version = env!("CARGO_PKG_VERSION");
+        match &self.contact {
+            Some(contact) => format!("{name}/{version} (+{contact})"),
+            None => format!("{name}/{version}"),
+        }
+    }
+
+    fn client(&self) -> Result<&Client> {
+        self.client.get_or_try_init(|| self.new_client())
+    }
+
+    fn new_client(&self) -> Result<Client> {
+        let session = match &self.session {
+            Some(Session::Value(session)) => session.clone(),
+            Some(Session::File(path)) => read_session_file(path)?,
+            None => env_var(self.year, "SESSION").with_context(|| {
+                format!(
+                    "Environment variables {} and AOC_SESSION are unset, and profile {} has no session.
+Set one to the value of the `session` cookie from the advent of code website.",
+                    year_var(self.year, "SESSION"),
+                    self.profile
+                )
+            })?,
+        };
 
         let mut headers = HeaderMap::new();
         let ck = HeaderValue::from_str(&format!("session={}", session))?;
         headers.insert("cookie", ck);
 
-        let c = Client::builder().default_headers(headers).build()?;
-
-        Ok(InputSource { client: c })
+        Ok(Client::builder()
+            .user_agent(self.user_agent())
+            .default_headers(headers)
+            .build()?)
     }
 
+    /// Returns the input of `day` from the cache, or downloads it.
     pub fn get(&self, day: usize) -> Result<String> {
-        if let Some(s) = InputSource::get_cache(day) {
+        if let Some(s) = self.get_cache(day) {
             return Ok(s);
         }
 
+        self.check_fetch(day, "input")?;
+
         let r = self.get_https(day)?;
 
-        InputSource::put_cache(day, &r);
+        self.put_cache(day, &r);
 
         Ok(r)
     }
 
+    /// Returns the puzzle page of `day` from the cache, or downloads it.
+    pub fn get_puzzle(&self, day: usize) -> Result<String> {
+        if let Some(s) = self
+            .puzzle_path(day)
+            .and_then(|p| fs::read_to_string(p).ok())
+        {
+            return Ok(s);
+        }
+        self.fetch_puzzle(day)
+    }
+
+    /// Downloads the puzzle page of `day` and updates the cache,
+    /// eg. to get the description of part two after solving part one.
+    pub fn fetch_puzzle(&self, day: usize) -> Result<String> {
+        self.check_fetch(day, "puzzle")?;
+
+        let url = self.puzzle_url(day);
+        let html = self.fetch(&url, &format!("puzzle of day {day}"))?;
+
+        if let Some(path) = self.puzzle_path(day) {
+            let r = path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::write(&path, &html));
+            if let Err(err) = r {
+                eprintln!("error writing cache file {:?}: {}", path, err);
+            }
+        }
+        Ok(html)
+    }
+
+    /// Example inputs of `day` extracted from the puzzle page.
+    pub fn examples(&self, day: usize) -> Result<Vec<String>> {
+        Ok(extract_examples(&self.get_puzzle(day)?))
+    }
+
+    /// Returns example `n` (starting at 1) of `day`.
+    ///
+    /// The puzzle page is downloaded again if the example is missing from
+    /// a cached page that does not have the description of part two yet.
+    pub fn example(&self, day: usize, n: usize) -> Result<String> {
+        let mut html = self.get_puzzle(day)?;
+        let mut v = extract_examples(&html);
+        if n > v.len() && !has_part_two(&html) && !self.offline {
+            html = self.fetch_puzzle(day)?;
+            v = extract_examples(&html);
+        }
+        match n.checked_sub(1).and_then(|i| v.get(i)) {
+            Some(s) => Ok(s.clone()),
+            None => bail!("day {day} has no example {n}, only {}", v.len()),
+        }
+    }
+
+    // check_fetch fails if what of day can't or shouldn't be downloaded now.
+    fn check_fetch(&self, day: usize, what: &str) -> Result<()> {
+        if self.offline {
+            match &self.cache_dir {
+                Some(dir) => bail!("{what} of day {day} is not cached in {dir:?} (offline)"),
+                None => bail!("{what} of day {day} is not cached (offline)"),
+            }
+        }
+
+        if self.base_url() == DEFAULT_BASE_URL {
+            check_unlocked(self.year, day, SystemTime::now())?;
+        }
+        Ok(())
+    }
+
+    fn input_url(&self, day: usize) -> String {
+        format!("{}/{}/day/{}/input", self.base_url(), self.year, day)
+    }
+
+    fn puzzle_url(&self, day: usize) -> String {
+        format!("{}/{}/day/{}", self.base_url(), self.year, day)
+    }
+
+    fn puzzle_path(&self, day: usize) -> Option<PathBuf> {
+        Some(
+            self.cache_path()?
+                .join("puzzles")
+                .join(format!("{day}.html")),
+        )
+    }
+
     fn get_https(&self, day: usize) -> Result<String> {
-        const AOC_YEAR_FULL: u32 = 2000 + AOC_YEAR;
-        let url = format!(
-            "https://adventofcode.com/{}/day/{}/input",
-            AOC_YEAR_FULL, day
-        );
-        Ok(self.client.get(&url[..]).send()?.text()?)
+        let url = self.input_url(day);
+        let body = self.fetch(&url, &format!("input of day {day}"))?;
+        check_input(&body).with_context(|| format!("invalid input of day {day} from {url}"))?;
+        Ok(body)
+    }
+
+    /// Submits `answer` of `part` of `day` and returns the verdict of the website.
+    pub fn submit(&self, day: usize, part: u8, answer: &str) -> Result<Verdict> {
+        if self.offline {
+            bail!("answers can't be submitted offline");
+        }
+        let url = format!("{}/{}/day/{}/answer", self.base_url(), self.year, day);
+        let level = part.to_string();
+        let form = [("level", level.as_str()), ("answer", answer)];
+        let what = format!("answer of day {day} part {part}");
+        let html = self.send(&what, false, |client| client.post(&url).form(&form))?;
+        Ok(parse_response(&html))
     }
 
-    fn cache_folder() -> String {
-        format!("aoc{}", AOC_YEAR)
+    /// Returns the name of the user logged in with the session,
+    /// or fails if the session is not valid.
+    pub fn whoami(&self) -> Result<String> {
+        if self.offline {
+            bail!("the session can't be checked offline");
+        }
+        let url = format!("{}/{}", self.base_url(), self.year);
+        let html = self.fetch(&url, &format!("event page of {}", self.year))?;
+        match user_name(&html) {
+            Some(name) => Ok(name),
+            None => bail!("{}", session_error_message()),
+        }
     }
 
-    fn get_cache(day: usize) -> Option<String> {
+    // fetch downloads what from url, retrying transient errors.
+    fn fetch(&self, url: &str, what: &str) -> Result<String> {
+        self.send(what, true, |client| client.get(url))
+    }
+
+    // send sends the request made by req and returns the response body.
+    // Connection errors are retried, and error responses too if the request
+    // is idempotent.
+    fn send(
+        &self,
+        what: &str,
+        idempotent: bool,
+        req: impl Fn(&Client) -> RequestBuilder,
+    ) -> Result<String> {
+        let client = self.client()?;
+
+        let mut retry = 0;
+        let (status, body) = loop {
+            self.wait_turn();
+            let r = req(client).send().and_then(|resp| {
+                let status = resp.status();
+                Ok((status, resp.text()?))
+            });
+            let transient = match &r {
+                Ok((status, _)) => idempotent && is_transient(*status),
+                Err(err) => err.is_connect() || (idempotent && err.is_timeout()),
+            };
+            if !transient || retry >= self.retries {
+                break r.with_context(|| format!("error sending request for {what}"))?;
+            }
+            thread::sleep(self.backoff * 2u32.saturating_pow(retry));
+            retry += 1;
+        };
+
+        if !status.is_success() {
+            let err = status_error(what, status, &body);
+            return Err(match retry {
+                0 => err,
+                _ => err.context(format!("after {} retries", retry)),
+            });
+        }
+        Ok(body)
+    }
+
+    // wait_turn waits until the minimum interval has passed since the last request.
+    fn wait_turn(&self) {
+        // the lock is held while waiting to space out requests from several threads
+        let mut last = self.last_request.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(t) = *last {
+            let next = t + self.min_interval;
+            let now = Instant::now();
+            if next > now {
+                thread::sleep(next - now);
+            }
+        }
+        *last = Some(Instant::now());
+    }
+
+    fn cache_folder(year: u32) -> String {
+        format!("aoc{}", year % 100)
+    }
+
+    fn default_cache_dir(year: u32) -> Option<PathBuf> {
         let base_dirs = BaseDirs::new()?;
 
         let mut path = PathBuf::new();
         path.push(base_dirs.cache_dir());
-        path.push(Self::cache_folder());
+        path.push(Self::cache_folder(year));
+        Some(path)
+    }
+
+    /// Folder of the cached inputs.
+    pub fn cache_path(&self) -> Option<PathBuf> {
+        self.cache_dir.clone()
+    }
+
+    /// Returns the cached input of `day`, if any.
+    pub fn get_cache(&self, day: usize) -> Option<String> {
+        let mut path = self.cache_path()?;
         path.push(day.to_string());
 
         fs::read_to_string(&path).ok()
     }
 
-    fn put_cache(day: usize, contents: &str) {
-        let base_dirs = match BaseDirs::new() {
+    fn put_cache(&self, day: usize, contents: &str) {
+        let mut path = match self.cache_path() {
             Some(x) => x,
             None => {
                 return;
             }
         };
 
-        let mut path = PathBuf::new();
-        path.push(base_dirs.cache_dir());
-        path.push(Self::cache_folder());
-        if !path.exists() {
-            if let Err(err) = fs::create_dir(&path) {
-                eprintln!("error creating cache dir {:?}: {}", path, err);
-                return;
-            }
+        if let Err(err) = fs::create_dir_all(&path) {
+            eprintln!("error creating cache dir {:?}: {}", path, err);
+            return;
         }
         path.push(day.to_string());
-        if let Err(err) = fs::write(&path, &contents) {
+        if let Err(err) = fs::write(&path, contents) {
             eprintln!("error writing cache file {:?}: {}", path, err);
+            return;
+        }
+
+        let meta = CacheMeta {
+            fetched: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            url: self.input_url(day),
+        };
+        path.set_extension("json");
+        let s = serde_json::to_string(&meta).expect("cache metadata serializes");
+        if let Err(err) = fs::write(&path, s) {
+            eprintln!("error writing cache metadata {:?}: {}", path, err);
+        }
+    }
+
+    /// Lists the cached inputs ordered by day.
+    pub fn cached(&self) -> Result<Vec<CacheEntry>> {
+        let Some(dir) = self.cache_path() else {
+            return Ok(vec![]);
+        };
+        let rd = match fs::read_dir(&dir) {
+            Ok(rd) => rd,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => {
+                return Err(err).with_context(|| format!("error reading cache dir {:?}", dir))
+            }
+        };
+
+        let mut v = vec![];
+        for entry in rd {
+            let entry = entry.with_context(|| format!("error reading cache dir {:?}", dir))?;
+            let Some(day) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+                continue;
+            };
+            let path = entry.path();
+            let size = entry.metadata()?.len();
+            let meta = fs::read_to_string(path.with_extension("json"))
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok());
+            v.push(CacheEntry {
+                day,
+                path,
+                size,
+                meta,
+            });
+        }
+        v.sort_by_key(|e| e.day);
+        Ok(v)
+    }
+
+    /// Removes the cached input and puzzle page of `day`, or all of them if None.
+    /// Returns the number of inputs removed.
+    pub fn purge(&self, day: Option<usize>) -> Result<usize> {
+        let mut n = 0;
+        for e in self.cached()? {
+            if day.is_some_and(|d| d != e.day) {
+                continue;
+            }
+            fs::remove_file(&e.path).with_context(|| format!("error removing {:?}", e.path))?;
+            let _ = fs::remove_file(e.path.with_extension("json"));
+            n += 1;
+        }
+        if let Some(dir) = self.cache_path() {
+            let puzzles = dir.join("puzzles");
+            let _ = match day {
+                Some(day) => fs::remove_file(puzzles.join(format!("{day}.html"))),
+                None => fs::remove_dir_all(puzzles),
+            };
+        }
+        Ok(n)
+    }
+}
+
+/// Cached input of a day.
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub day: usize,
+    pub path: PathBuf,
+    pub size: u64,
+
+    /// Metadata stored along with the input, missing for inputs cached by hand.
+    pub meta: Option<CacheMeta>,
+}
+
+impl CacheEntry {
+    /// Problems of the cached input, empty if it looks fine.
+    pub fn verify(&self) -> Vec<String> {
+        let s = match fs::read_to_string(&self.path) {
+            Ok(s) => s,
+            Err(err) => return vec![format!("unreadable: {}", err)],
+        };
+
+        let mut v = vec![];
+        if let Err(err) = check_input(&s) {
+            v.push(err.to_string());
+        }
+        if !s.ends_with('\n') {
+            v.push("missing trailing newline".to_string());
+        } else if s.ends_with("\n\n") {
+            v.push("extra trailing newlines".to_string());
         }
+        v
+    }
+}
+
+/// Metadata of a cached input, stored in a sidecar file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheMeta {
+    /// Time of fetching in seconds since the unix epoch.
+    pub fetched: u64,
+    pub url: String,
+}
+
+/// Checks that `s` looks like puzzle input, and not like an error page.
+pub fn check_input(s: &str) -> Result<()> {
+    if s.trim().is_empty() {
+        bail!("input is empty");
+    }
+    if is_login_page(s) {
+        bail!("{}", session_error_message());
+    }
+    let t = s.trim_start();
+    let head = t.get(..15).unwrap_or(t).to_ascii_lowercase();
+    if head.starts_with("<!doctype html") || head.starts_with("<html") {
+        bail!("response is an HTML page, not puzzle input");
+    }
+    Ok(())
+}
+
+// user_name finds the name of the logged in user in the header of a page.
+fn user_name(html: &str) -> Option<String> {
+    const USER: &str = "<div class=\"user\">";
+    let start = html.find(USER)? + USER.len();
+    let rest = &html[start..];
+    let name = rest[..rest.find('<')?].trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn is_login_page(s: &str) -> bool {
+    s.contains("Please log in")
+}
+
+fn session_error_message() -> &'static str {
+    "the session is invalid or expired, update it from the `session` cookie of the website"
+}
+
+// year_var returns the name of the environment variable `name` of year.
+fn year_var(year: u32, name: &str) -> String {
+    format!("AOC{:02}_{}", year % 100, name)
+}
+
+// env_var_os returns the value of the environment variable `name` of year,
+// or the one common to all years if it is unset or empty.
+fn env_var_os(year: u32, name: &str) -> Option<std::ffi::OsString> {
+    [year_var(year, name), format!("AOC_{}", name)]
+        .into_iter()
+        .filter_map(env::var_os)
+        .find(|v| !v.is_empty())
+}
+
+fn env_var(year: u32, name: &str) -> Option<String> {
+    env_var_os(year, name).and_then(|v| v.into_string().ok())
+}
+
+/// Time when the puzzle of `day` of `year` is released, at midnight EST (UTC-5).
+pub fn unlock_time(year: u32, day: usize) -> SystemTime {
+    let days = days_from_civil(year as i64, 12, day as i64);
+    let secs = days * 86400 + 5 * 3600;
+    UNIX_EPOCH + Duration::from_secs(secs.max(0) as u64)
+}
+
+// check_unlocked fails if the puzzle of day of year is not released at now.
+fn check_unlocked(year: u32, day: usize, now: SystemTime) -> Result<()> {
+    if !(1..=25).contains(&day) {
+        bail!("there is no puzzle for day {day}");
+    }
+    let unlock = unlock_time(year, day);
+    if now < unlock {
+        let secs = unlock.duration_since(now).unwrap_or_default().as_secs();
+        bail!(
+            "day {day} is not unlocked yet, it unlocks in {}h{:02}m{:02}s",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        );
+    }
+    Ok(())
+}
+
+// days_from_civil returns the number of days since the unix epoch
+// of a date in the proleptic Gregorian calendar.
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+// status_error explains the unsuccessful response to the request of what.
+fn status_error(what: &str, status: StatusCode, body: &str) -> anyhow::Error {
+    match status.as_u16() {
+        _ if is_login_page(body) => anyhow!("{} ({})", session_error_message(), status),
+        400 | 401 | 403 => anyhow!("{} ({})", session_error_message(), status),
+        404 => anyhow!("{what} not found ({status}), it may not be unlocked yet"),
+        429 => anyhow!("too many requests ({status}), try again later"),
+        500..=599 => anyhow!("server error ({status})"),
+        _ => anyhow!("unexpected response {status}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        assert!(check_input("1abc2\n").is_ok());
+        assert!(check_input("\n").is_err());
+        assert!(check_input("<!DOCTYPE html>\n<html></html>").is_err());
+        assert!(check_input("<html lang=\"en-us\">").is_err());
+        assert!(check_input("\n<html>").is_err());
+        let err =
+            check_input("Puzzle inputs differ by user.  Please log in to get your puzzle input.");
+        assert!(err.unwrap_err().to_string().contains("session"));
+
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        let unlock = unlock_time(2023, 1).duration_since(UNIX_EPOCH).unwrap();
+        assert_eq!(unlock.as_secs(), 1701406800); // 2023-12-01T05:00:00Z
+        let before = unlock_time(2023, 5) - Duration::from_secs(3661);
+        let err = check_unlocked(2023, 5, before).unwrap_err().to_string();
+        assert!(err.contains("unlocks in 1h01m01s"), "{err}");
+        assert!(check_unlocked(2023, 5, unlock_time(2023, 5)).is_ok());
+        assert!(check_unlocked(2023, 26, unlock_time(2023, 25)).is_err());
+        assert!(check_unlocked(2022, 1, unlock_time(2023, 1)).is_ok());
+
+        assert_eq!(year_var(2022, "SESSION"), "AOC22_SESSION");
+
+        let header = r#"<div><div class="user">Jane Doe <span class="star-count">12*</span></div>"#;
+        assert_eq!(user_name(header).as_deref(), Some("Jane Doe"));
+        assert_eq!(
+            user_name(r#"<a href="/2023/auth/login">[Log In]</a>"#),
+            None
+        );
+        let is = InputSource::for_year(2022).with_base_url("http://mirror/");
+        assert_eq!(is.input_url(3), "http://mirror/2022/day/3/input");
     }
 }
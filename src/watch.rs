@@ -0,0 +1,137 @@
+//! Polling of files for changes, used by the watch mode of the runner.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Files watched for changes of their modification time or size.
+pub struct Watcher {
+    files: Vec<(PathBuf, Option<Stamp>)>,
+
+    // folders scanned for new files with an extension
+    dirs: Vec<(PathBuf, String)>,
+}
+
+// Stamp identifies a version of a file.
+type Stamp = (SystemTime, u64);
+
+impl Watcher {
+    /// Watches `paths`, which need not exist yet.
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let files = paths
+            .into_iter()
+            .map(|p| {
+                let s = stamp(&p);
+                (p, s)
+            })
+            .collect();
+        Self {
+            files,
+            dirs: vec![],
+        }
+    }
+
+    /// Also watches the files with extension `ext` in `dir` and its subfolders,
+    /// including the ones created later.
+    pub fn with_dir(mut self, dir: &Path, ext: &str) -> Self {
+        for path in find_files(dir, ext) {
+            if !self.files.iter().any(|(p, _)| *p == path) {
+                let s = stamp(&path);
+                self.files.push((path, s));
+            }
+        }
+        self.dirs.push((dir.to_path_buf(), ext.to_string()));
+        self
+    }
+
+    /// Returns the files that changed, appeared or disappeared since
+    /// the watcher was created or this was last called.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        for (dir, ext) in &self.dirs {
+            for path in find_files(dir, ext) {
+                if !self.files.iter().any(|(p, _)| *p == path) {
+                    self.files.push((path, None));
+                }
+            }
+        }
+
+        let mut v = vec![];
+        for (path, last) in &mut self.files {
+            let s = stamp(path);
+            if s != *last {
+                *last = s;
+                v.push(path.clone());
+            }
+        }
+        v
+    }
+
+    /// Waits until some files change, checking them every `interval`.
+    pub fn wait(&mut self, interval: Duration) -> Vec<PathBuf> {
+        loop {
+            let v = self.changed();
+            if !v.is_empty() {
+                return v;
+            }
+            thread::sleep(interval);
+        }
+    }
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let m = fs::metadata(path).ok()?;
+    Some((m.modified().ok()?, m.len()))
+}
+
+/// Lists the files with extension `ext` in `dir` and its subfolders.
+pub fn find_files(dir: &Path, ext: &str) -> Vec<PathBuf> {
+    let mut v = vec![];
+    let Ok(rd) = fs::read_dir(dir) else {
+        return v;
+    };
+    for entry in rd.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            v.extend(find_files(&path, ext));
+        } else if path.extension().is_some_and(|x| x == ext) {
+            v.push(path);
+        }
+    }
+    v.sort();
+    v
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let dir = std::env::temp_dir().join(format!("aoc23-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (a, b) = (dir.join("a.rs"), dir.join("b.rs"));
+        fs::write(&a, "1").unwrap();
+
+        let mut w = Watcher::new([a.clone(), b.clone()]);
+        assert!(w.changed().is_empty());
+        fs::write(&b, "2").unwrap();
+        assert_eq!(w.changed(), vec![b.clone()]);
+        fs::write(&a, "12").unwrap();
+        assert_eq!(w.wait(Duration::from_millis(1)), vec![a.clone()]);
+        assert!(w.changed().is_empty());
+
+        assert_eq!(find_files(&dir, "rs"), [a.clone(), b.clone()]);
+
+        let mut w = Watcher::new([]).with_dir(&dir, "rs");
+        let c = dir.join("sub").join("c.rs");
+        fs::create_dir_all(c.parent().unwrap()).unwrap();
+        fs::write(&c, "3").unwrap();
+        fs::write(dir.join("notes.txt"), "4").unwrap();
+        assert_eq!(w.changed(), vec![c.clone()]);
+        fs::remove_file(&a).unwrap();
+        assert_eq!(w.changed(), vec![a]);
+        assert!(w.changed().is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use aoc23::InputSource;
+use common::{temp_dir, Response, Server};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+// source fetches from url without waiting between requests.
+fn source(url: &str) -> InputSource {
+    InputSource::new()
+        .with_base_url(url)
+        .with_min_interval(Duration::ZERO)
+        .with_retries(2, Duration::from_millis(1))
+}
+
+#[test]
+fn fetch_and_cache() {
+    let server = Server::start(|req| match req.path.as_str() {
+        "/2023/day/1/input" => Response::ok("1abc2\n"),
+        _ => Response::new(404, "Not Found"),
+    });
+    let cache = temp_dir("fetch_and_cache");
+
+    let is = source(&format!("{}/", server.url))
+        .with_session("secret")
+        .with_cache_dir(cache.join("nested/dir"));
+    assert_eq!(is.get(1).unwrap(), "1abc2\n");
+
+    let reqs = server.requests();
+    assert_eq!(reqs.len(), 1);
+    assert_eq!(reqs[0].method, "GET");
+    assert_eq!(reqs[0].header("cookie"), Some("session=secret"));
+
+    // cached inputs are available offline
+    let cached = std::fs::read_to_string(cache.join("nested/dir/1")).unwrap();
+    assert_eq!(cached, "1abc2\n");
+    let is = InputSource::new()
+        .with_base_url(&server.url)
+        .with_cache_dir(cache.join("nested/dir"))
+        .offline(true);
+    assert_eq!(is.get(1).unwrap(), "1abc2\n");
+    assert!(is.get(2).is_err());
+    assert_eq!(server.requests().len(), 1);
+
+    let cached = is.cached().unwrap();
+    assert_eq!(cached.len(), 1);
+    assert_eq!(cached[0].day, 1);
+    assert_eq!(cached[0].size, 6);
+    let meta = cached[0].meta.as_ref().unwrap();
+    assert_eq!(meta.url, format!("{}/2023/day/1/input", server.url));
+    assert!(cached[0].verify().is_empty());
+
+    assert_eq!(is.purge(Some(2)).unwrap(), 0);
+    assert_eq!(is.purge(None).unwrap(), 1);
+    assert!(is.get_cache(1).is_none());
+
+    std::fs::remove_dir_all(cache).unwrap();
+}
+
+#[test]
+fn server_unreachable() {
+    // bind and drop a listener to get a port that is not listening
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let cache = temp_dir("server_unreachable");
+
+    let is = source(&format!("http://{}", addr))
+        .with_session("secret")
+        .with_cache_dir(&cache);
+    assert!(is.get(1).is_err());
+    assert!(!cache.join("1").exists());
+
+    std::fs::remove_dir_all(cache).unwrap();
+}
+
+#[test]
+fn bad_responses() {
+    let server = Server::start(|req| match req.path.as_str() {
+        "/2023/day/1/input" => Response::new(
+            400,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+        ),
+        "/2023/day/2/input" => Response::new(
+            404,
+            "Please don't repeatedly request this endpoint before it unlocks!",
+        ),
+        "/2023/day/3/input" => Response::new(429, "Too Many Requests"),
+        "/2023/day/4/input" => Response::new(500, "Internal Server Error"),
+        "/2023/day/5/input" => Response::html(200, "<!DOCTYPE html>\n<html></html>\n"),
+        "/2023/day/6/input" => Response::ok(""),
+        _ => Response::ok("1abc2\n"),
+    });
+    let cache = temp_dir("bad_responses");
+
+    let is = source(&server.url)
+        .with_session("expired")
+        .with_cache_dir(&cache);
+
+    let err = |day| format!("{:#}", is.get(day).unwrap_err());
+    assert!(err(1).contains("session is invalid or expired"));
+    assert!(err(2).contains("not be unlocked yet"));
+    assert!(err(3).contains("too many requests"));
+    assert!(err(4).contains("server error"));
+    assert!(err(4).contains("after 2 retries"));
+    assert!(err(5).contains("HTML page"));
+    assert!(err(6).contains("empty"));
+
+    // nothing is cached from bad responses
+    assert_eq!(std::fs::read_dir(&cache).unwrap().count(), 0);
+
+    assert!(is.get(7).is_ok());
+    assert!(cache.join("7").exists());
+
+    std::fs::remove_dir_all(cache).unwrap();
+}
+
+#[test]
+fn polite_fetching() {
+    let failures = AtomicUsize::new(2);
+    let server = Server::start(move |_| {
+        let left = failures.load(Ordering::SeqCst);
+        if left > 0 {
+            failures.store(left - 1, Ordering::SeqCst);
+            Response::new(503, "Service Unavailable")
+        } else {
+            Response::ok("1abc2\n")
+        }
+    });
+    let cache = temp_dir("polite_fetching");
+
+    let is = source(&server.url)
+        .with_session("secret")
+        .with_contact("me@example.com")
+        .with_min_interval(Duration::from_millis(50))
+        .with_cache_dir(&cache);
+
+    // transient errors are retried
+    let start = Instant::now();
+    assert_eq!(is.get(1).unwrap(), "1abc2\n");
+    assert_eq!(is.get(2).unwrap(), "1abc2\n");
+    let reqs = server.requests();
+    assert_eq!(reqs.len(), 4);
+
+    // requests are spaced by the minimum interval
+    assert!(start.elapsed() >= Duration::from_millis(150));
+
+    let ua = reqs[0].header("user-agent").unwrap();
+    assert!(ua.starts_with("aoc23/"), "{ua}");
+    assert!(ua.contains("me@example.com"), "{ua}");
+
+    std::fs::remove_dir_all(cache).unwrap();
+}
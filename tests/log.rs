@@ -0,0 +1,44 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const DAY15: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7\n";
+
+// run runs day 15 on the example input with args, returning stdout and stderr.
+fn run(args: &[&str]) -> (String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_aoc23"))
+        .args(["--stdin", "-p", "2"])
+        .args(args)
+        .arg("15")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(DAY15.as_bytes())
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&out.stderr).into_owned();
+    assert!(out.status.success(), "{stdout}{stderr}");
+    (stdout, stderr)
+}
+
+#[test]
+fn log_filter() {
+    let (stdout, stderr) = run(&["--log", "day15=trace"]);
+    assert!(stdout.contains("145"), "{stdout}");
+    assert!(stderr.contains("Box 0: [rn 1] [cm 2] -> 5"), "{stderr}");
+    assert!(!stdout.contains("Box"), "{stdout}");
+
+    let (_, stderr) = run(&["--log", "info,day14=trace"]);
+    assert!(!stderr.contains("Box"), "{stderr}");
+
+    // -v logs at debug level, and the days are not taken as its value
+    let (stdout, stderr) = run(&["-v"]);
+    assert!(stdout.contains("145"), "{stdout}");
+    assert!(!stderr.contains("Box"), "{stderr}");
+}
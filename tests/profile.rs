@@ -0,0 +1,153 @@
+mod common;
+
+use aoc23::config::{Config, DEFAULT_PROFILE};
+use aoc23::InputSource;
+use common::{temp_dir, Response, Server};
+use std::process::Command;
+use std::time::Duration;
+
+fn event_page(req: &common::Request) -> Response {
+    let user = match req.header("cookie") {
+        Some("session=home") => {
+            r#"<div class="user">Home User <span class="star-count">50*</span></div>"#
+        }
+        Some("session=work") => r#"<div class="user">Work User</div>"#,
+        _ => r#"<div><a href="/2023/auth/login">[Log In]</a></div>"#,
+    };
+    Response::html(
+        200,
+        &format!("<!DOCTYPE html>\n<html><header>{user}</header></html>\n"),
+    )
+}
+
+#[test]
+fn profiles() {
+    let dir = temp_dir("profiles");
+    std::fs::write(dir.join("work-session"), "work\n").unwrap();
+    let config = Config::parse(&format!(
+        "session = home\n\n[work]\nsession_file = {}\n",
+        dir.join("work-session").display()
+    ))
+    .unwrap();
+
+    let server = Server::start(event_page);
+    let source = |profile| {
+        InputSource::new()
+            .with_cache_dir(dir.join("aoc23"))
+            .with_profile(config.profile(profile).unwrap())
+            .with_base_url(&server.url)
+            .with_min_interval(Duration::ZERO)
+    };
+
+    let work = source("work");
+    assert_eq!(work.whoami().unwrap(), "Work User");
+    assert_eq!(work.profile(), "work");
+    assert_eq!(work.cache_path(), Some(dir.join("aoc23-work")));
+
+    // the default profile shares the cache of the environment
+    let home = source(DEFAULT_PROFILE);
+    assert_eq!(home.cache_path(), Some(dir.join("aoc23")));
+
+    let bad = InputSource::new()
+        .with_base_url(&server.url)
+        .with_session("expired")
+        .with_min_interval(Duration::ZERO);
+    assert!(format!("{:#}", bad.whoami().unwrap_err()).contains("session"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn whoami_command() {
+    let dir = temp_dir("whoami_command");
+    let config = dir.join("config");
+    std::fs::write(&config, "session = home\n[work]\nsession = work\n").unwrap();
+    let server = Server::start(event_page);
+
+    let whoami = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_aoc23"))
+            .args(["--base-url", &server.url, "--config"])
+            .arg(&config)
+            .args(args)
+            .arg("whoami")
+            .env_remove("AOC23_SESSION")
+            .env_remove("AOC_SESSION")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&out.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&out.stderr).into_owned();
+        (out.status.success(), stdout + &stderr)
+    };
+
+    let (ok, out) = whoami(&[]);
+    assert!(ok, "{out}");
+    assert!(
+        out.contains("Profile default: logged in as Home User"),
+        "{out}"
+    );
+
+    let (ok, out) = whoami(&["--profile", "work"]);
+    assert!(ok, "{out}");
+    assert!(
+        out.contains("Profile work: logged in as Work User"),
+        "{out}"
+    );
+
+    let (ok, out) = whoami(&["--profile", "play"]);
+    assert!(!ok);
+    assert!(
+        out.contains("unknown profile play, available: default, work"),
+        "{out}"
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn profile_cache_dir() {
+    let dir = temp_dir("profile_cache_dir");
+    let config = dir.join("config");
+    std::fs::write(
+        &config,
+        format!(
+            "session = home\n[work]\nsession = work\n[old]\nsession_file = {}\n",
+            dir.join("missing").display()
+        ),
+    )
+    .unwrap();
+    let cache = dir.join("cache");
+    for profile in ["work", "old"] {
+        let dir = dir.join(format!("cache-{profile}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("1"), "x7y\n").unwrap();
+    }
+
+    let run = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_aoc23"))
+            .args(["--offline", "--config"])
+            .arg(&config)
+            .arg("--cache-dir")
+            .arg(&cache)
+            .args(args)
+            .args(["1", "-p", "1"])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&out.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&out.stderr).into_owned();
+        (out.status.success(), stdout + &stderr)
+    };
+
+    // the work profile does not share the cache of the default profile
+    let (ok, out) = run(&["--profile", "work"]);
+    assert!(ok, "{out}");
+    assert!(out.contains("77"), "{out}");
+    let (ok, out) = run(&[]);
+    assert!(!ok, "{out}");
+
+    // the session file is read only to fetch inputs
+    let (ok, out) = run(&["--profile", "old"]);
+    assert!(ok, "{out}");
+    assert!(out.contains("77"), "{out}");
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
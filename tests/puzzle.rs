@@ -0,0 +1,71 @@
+mod common;
+
+use aoc23::puzzle::{extract_examples, has_part_two};
+use aoc23::InputSource;
+use common::{temp_dir, Response, Server};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const PART1: &str = include_str!("fixtures/day01_part1.html");
+const FULL: &str = include_str!("fixtures/day01.html");
+
+#[test]
+fn fixtures() {
+    let v = extract_examples(PART1);
+    assert_eq!(v, vec!["1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n"]);
+    assert!(!has_part_two(PART1));
+
+    let v = extract_examples(FULL);
+    assert_eq!(v.len(), 2);
+    assert!(v[1].starts_with("two1nine\n"));
+    assert!(has_part_two(FULL));
+
+    let day = aoc23::day(2023, 1).unwrap();
+    let dr = day.run(&v[0], Some(1));
+    assert_eq!(dr.parts[0].answer.as_deref().ok(), Some("142"));
+    let dr = day.run(&v[1], Some(2));
+    assert_eq!(dr.parts[0].answer.as_deref().ok(), Some("281"));
+}
+
+#[test]
+fn fetch_examples() {
+    // the description of part two appears after solving part one
+    let solved = Arc::new(AtomicBool::new(false));
+    let server = Server::start({
+        let solved = solved.clone();
+        move |req| match req.path.as_str() {
+            "/2023/day/1" if solved.load(Ordering::SeqCst) => Response::html(200, FULL),
+            "/2023/day/1" => Response::html(200, PART1),
+            _ => Response::new(404, "Not Found"),
+        }
+    });
+    let cache = temp_dir("fetch_examples");
+
+    let is = InputSource::new()
+        .with_base_url(&server.url)
+        .with_session("secret")
+        .with_min_interval(Duration::ZERO)
+        .with_cache_dir(&cache);
+
+    assert!(is.example(1, 1).unwrap().starts_with("1abc2\n"));
+    assert!(is.example(1, 2).is_err());
+    assert_eq!(server.requests().len(), 2);
+    assert!(cache.join("puzzles/1.html").exists());
+
+    // missing examples are looked for on a fresh page
+    solved.store(true, Ordering::SeqCst);
+    assert!(is.example(1, 2).unwrap().starts_with("two1nine\n"));
+    assert_eq!(server.requests().len(), 3);
+
+    let is = is.offline(true);
+    assert_eq!(is.examples(1).unwrap().len(), 2);
+    assert!(is.example(1, 3).is_err());
+    assert!(is.example(2, 1).is_err());
+    assert_eq!(server.requests().len(), 3);
+
+    is.purge(Some(1)).unwrap();
+    assert!(!cache.join("puzzles/1.html").exists());
+
+    std::fs::remove_dir_all(cache).unwrap();
+}
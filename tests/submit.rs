@@ -0,0 +1,126 @@
+mod common;
+
+use aoc23::submit::{Attempts, Verdict};
+use aoc23::InputSource;
+use common::{temp_dir, Response, Server};
+use std::process::Command;
+use std::time::Duration;
+
+fn answer_page(text: &str) -> Response {
+    let html = format!(
+        "<!DOCTYPE html>\n<html><main>\n<article><p>{text}</p></article>\n</main></html>\n"
+    );
+    Response::html(200, &html)
+}
+
+#[test]
+fn submit_answer() {
+    const RIGHT: &str = "That's the right answer!  You are <span>one gold star</span> closer.";
+    const LOW: &str = "That's not the right answer; your answer is too low.";
+    let server = Server::start(|req| match (req.method.as_str(), req.path.as_str()) {
+        ("POST", "/2023/day/1/answer") if req.body.contains("answer=142") => answer_page(RIGHT),
+        ("POST", "/2023/day/1/answer") => answer_page(LOW),
+        _ => Response::new(404, "Not Found"),
+    });
+    let cache = temp_dir("submit_answer");
+
+    let is = InputSource::new()
+        .with_base_url(&server.url)
+        .with_session("secret")
+        .with_min_interval(Duration::ZERO)
+        .with_cache_dir(&cache);
+    assert_eq!(is.submit(1, 2, "52136").unwrap(), Verdict::TooLow);
+    assert_eq!(is.submit(1, 1, "142").unwrap(), Verdict::Correct);
+
+    let reqs = server.requests();
+    assert_eq!(reqs[0].body, "level=2&answer=52136");
+    assert_eq!(reqs[0].header("cookie"), Some("session=secret"));
+    assert!(is.offline(true).submit(1, 1, "142").is_err());
+
+    std::fs::remove_dir_all(cache).unwrap();
+}
+
+#[test]
+fn submit_command() {
+    let server = Server::start(|req| match req.path.as_str() {
+        "/2023/day/1/answer" => {
+            answer_page("That's not the right answer; your answer is too high.")
+        }
+        _ => Response::new(404, "Not Found"),
+    });
+    let cache = temp_dir("submit_command");
+    std::fs::write(
+        cache.join("1"),
+        "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n",
+    )
+    .unwrap();
+
+    let submit = || {
+        Command::new(env!("CARGO_BIN_EXE_aoc23"))
+            .args(["--base-url", &server.url, "--cache-dir"])
+            .arg(&cache)
+            .args(["submit", "1", "1"])
+            .env("AOC23_SESSION", "secret")
+            .output()
+            .unwrap()
+    };
+
+    let out = submit();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("too high"));
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(server.requests()[0].body, "level=1&answer=142");
+
+    let attempts = Attempts::new(cache.join("submissions")).load(1).unwrap();
+    assert_eq!(attempts.len(), 1);
+    assert_eq!(attempts[0].answer, "142");
+    assert_eq!(attempts[0].verdict, Verdict::TooHigh);
+
+    // known wrong answers are not submitted again
+    let out = submit();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("not submitting"));
+    assert_eq!(server.requests().len(), 1);
+
+    std::fs::remove_dir_all(cache).unwrap();
+}
+
+#[test]
+fn submit_puzzle_input() {
+    let server = Server::start(|req| match req.path.as_str() {
+        "/2023/day/1/answer" => answer_page("That's not the right answer."),
+        _ => Response::new(404, "Not Found"),
+    });
+    let cache = temp_dir("submit_puzzle_input");
+    std::fs::write(cache.join("1"), "x7y\n8z9\n").unwrap();
+    std::fs::create_dir_all(cache.join("puzzles")).unwrap();
+    std::fs::write(
+        cache.join("puzzles").join("1.html"),
+        include_str!("fixtures/day01.html"),
+    )
+    .unwrap();
+
+    let submit = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_aoc23"))
+            .args(["--base-url", &server.url, "--cache-dir"])
+            .arg(&cache)
+            .args(args)
+            .args(["submit", "1", "1"])
+            .env("AOC23_SESSION", "secret")
+            .output()
+            .unwrap()
+    };
+
+    // the answer of an example is never posted
+    let out = submit(&["--example", "1"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--example"));
+    assert!(server.requests().is_empty());
+
+    let out = submit(&[]);
+    assert!(!out.status.success());
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(server.requests()[0].body, "level=1&answer=166");
+
+    std::fs::remove_dir_all(cache).unwrap();
+}